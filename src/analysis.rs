@@ -0,0 +1,155 @@
+use crate::{
+    game::Graph,
+    optimal::{is_solvable, Solution},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopNumber {
+    Exactly(u8),
+    /// The graph needs more than this many cops.
+    MoreThan(u8),
+    /// The graph is too large to solve for enough cops.
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureTime {
+    /// The number of robber steps the cops need to capture the robber with optimal play.
+    Steps(u32),
+    /// The robber can escape forever.
+    Never,
+    /// The graph is too large to solve.
+    Unknown,
+}
+
+/// Properties of a graph used to choose graphs for experiments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphAnalysis {
+    /// Whether the graph is dismantlable, i.e. a single cop can always capture the robber.
+    pub dismantlable: bool,
+    pub cop_number: CopNumber,
+    /// The capture time for the number of cops the analysis was made for.
+    pub capture_time: CaptureTime,
+}
+
+impl GraphAnalysis {
+    /// Analyses the graph. The cop number is only computed up to max_cops.
+    pub fn new(graph: &Graph, number_of_cops: u8, max_cops: u8) -> GraphAnalysis {
+        GraphAnalysis {
+            dismantlable: is_dismantlable(graph),
+            // We never stop, so we always find the cop number.
+            cop_number: cop_number(graph, max_cops, || false).unwrap(),
+            capture_time: capture_time(graph, number_of_cops),
+        }
+    }
+}
+
+/// Whether we can repeatedly remove a dominated vertex (a vertex v whose closed
+/// neighbourhood is contained in the closed neighbourhood of another vertex u)
+/// until a single vertex is left. These are exactly the graphs where one cop wins.
+pub fn is_dismantlable(graph: &Graph) -> bool {
+    let number_of_vertices = graph.vertices.len();
+    let mut removed = vec![false; number_of_vertices];
+    let mut remaining = number_of_vertices;
+
+    // Whether v and u are the same or adjacent.
+    let closed_neighbours = |v: usize, u: usize| v == u || graph.adjacency_list[v].contains(&u);
+
+    let mut removed_any = true;
+    while remaining > 1 && removed_any {
+        removed_any = false;
+        for v in 0..number_of_vertices {
+            if removed[v] {
+                continue;
+            }
+            let dominated = graph.adjacency_list[v].iter().any(|&u| {
+                !removed[u]
+                    && graph.adjacency_list[v]
+                        .iter()
+                        .all(|&w| removed[w] || closed_neighbours(u, w))
+            });
+            if dominated {
+                removed[v] = true;
+                remaining -= 1;
+                removed_any = true;
+                if remaining == 1 {
+                    break;
+                }
+            }
+        }
+    }
+
+    remaining <= 1
+}
+
+/// The smallest number of cops which can always capture the robber, computed up to max_cops.
+/// As this solves the game for every number of cops, stop is called before each of them,
+/// and we return None if it returns true.
+pub fn cop_number(graph: &Graph, max_cops: u8, stop: impl Fn() -> bool) -> Option<CopNumber> {
+    if graph.vertices.is_empty() {
+        return Some(CopNumber::Exactly(0));
+    }
+    if is_dismantlable(graph) {
+        return Some(CopNumber::Exactly(1));
+    }
+    for number_of_cops in 2..=max_cops {
+        if !is_solvable(graph, number_of_cops) {
+            return Some(CopNumber::Unknown);
+        }
+        if stop() {
+            return None;
+        }
+        if Solution::new(graph, number_of_cops)
+            .capture_time()
+            .is_some()
+        {
+            return Some(CopNumber::Exactly(number_of_cops));
+        }
+    }
+    Some(CopNumber::MoreThan(max_cops))
+}
+
+/// The number of robber steps number_of_cops cops need to capture the robber with optimal play.
+pub fn capture_time(graph: &Graph, number_of_cops: u8) -> CaptureTime {
+    if !is_solvable(graph, number_of_cops) {
+        return CaptureTime::Unknown;
+    }
+    match Solution::new(graph, number_of_cops).capture_time() {
+        Some(steps) => CaptureTime::Steps(steps),
+        None => CaptureTime::Never,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::template_graphs;
+
+    #[test]
+    fn analyses_the_templates() {
+        let graphs = template_graphs();
+        // Paths are dismantlable, but the Hexagon needs two cops.
+        assert_eq!(
+            GraphAnalysis::new(&graphs[1], 1, 3),
+            GraphAnalysis {
+                dismantlable: true,
+                cop_number: CopNumber::Exactly(1),
+                capture_time: CaptureTime::Steps(2),
+            }
+        );
+        assert_eq!(
+            GraphAnalysis::new(&graphs[2], 1, 3),
+            GraphAnalysis {
+                dismantlable: false,
+                cop_number: CopNumber::Exactly(2),
+                capture_time: CaptureTime::Never,
+            }
+        );
+        assert_eq!(
+            cop_number(&graphs[2], 1, || false),
+            Some(CopNumber::MoreThan(1))
+        );
+        assert_eq!(cop_number(&graphs[2], 3, || true), None);
+        assert_eq!(capture_time(&graphs[2], 8), CaptureTime::Unknown);
+    }
+}
@@ -1,4 +1,21 @@
-use crate::game::{template_graphs, Algorithm, Cop, Game, Graph, Robber, Turn};
+use crate::{
+    analysis::{self, CaptureTime, CopNumber},
+    files::{load_file, save_file, PendingFile},
+    formats::{export_graph, import_graph, GraphFormat},
+    game::{
+        max_encodable_cops, template_graphs, ChosenMove, CopStrategy, Game, Graph, MatchRecord,
+        MatchResult, MenaceBrain, MenaceCop, MenaceRobber, MenaceSettings, Mover, RobberStrategy,
+        StrategyRegistry, StrategySettings, Turn,
+    },
+    generators::{largest_connected_component, GraphGenerator},
+    heuristics::{EvasiveSettings, TieBreaking},
+    human::{HumanCop, HumanRobber},
+    layouts::Layout,
+    mcts::MctsSettings,
+    optimal::{OptimalCop, OptimalRobber, Solution},
+    qlearning::{QLearningCop, QLearningRobber, QLearningSettings},
+    tournament::{Tournament, TournamentResults},
+};
 use egui::{
     containers::Frame,
     mutex::Mutex,
@@ -7,32 +24,131 @@ use egui::{
 };
 use std::{
     cmp::Ordering,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Arc,
+    },
     thread::{self, JoinHandle},
     time::Duration,
 };
 
 const ANIMATION_TIME: f32 = 0.5;
+// How often the game thread checks whether it should continue, while it waits.
+const POLL_TIME: Duration = Duration::from_millis(50);
+// The range of the speed of the game, as a multiple of the normal speed.
+const MIN_SPEED: f32 = 0.25;
+const MAX_SPEED: f32 = 8.0;
+const MAX_BATCH_SIZE: u32 = 1_000_000;
+// The most matches the replay tab can keep, as every turn of a recorded match is kept.
+const MAX_RECORDED_MATCHES: usize = 10_000;
+// The largest parameters of generated graphs.
+const MAX_GENERATED_VERTICES: usize = 100;
+const MAX_GRID_SIDE: usize = 10;
+const MAX_COMPLETE_SIZE: usize = 20;
+const MAX_HYPERCUBE_DIMENSION: usize = 6;
+// How often we show the progress of a batch of games.
+const BATCH_REPAINT_TIME: Duration = Duration::from_millis(100);
+// The most cops the game settings allow: the most MENACE and Q-learning can encode the
+// positions of on the largest generated graphs (8 cops), which is more than the game can be
+// solved or learned with on them anyway.
+const MAX_NUMBER_OF_COPS: u8 = max_encodable_cops(MAX_GENERATED_VERTICES);
+// The largest number of moves of a MENACE bag or Q-table we show,
+// as start bags can have millions of moves.
+const MAX_SHOWN_MOVES: usize = 100;
 const COP_COLOR: Color32 = Color32::from_rgb(230, 30, 10);
 const ROBBER_COLOR: Color32 = Color32::from_rgb(0, 100, 225);
 
 #[derive(PartialEq)]
-enum MenaceEditingVertex {
+enum BagEditingVertex {
     None,
     Cop(usize),
     Robber,
 }
 
-struct MenaceCopViewingState {
+// The state of exporting/importing a MENACE brain.
+struct BrainFileState {
+    path: String,
+    // The result of the last export/import.
+    message: Option<String>,
+    pending_file: Option<PendingFile>,
+}
+
+impl BrainFileState {
+    fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            message: None,
+            pending_file: None,
+        }
+    }
+}
+
+// The bag (or Q-table) of a learning player shown in the statistics panel.
+struct CopBagViewingState {
     bag_key: Option<(Vec<usize>, usize)>,
-    editing_vertex: MenaceEditingVertex,
+    editing_vertex: BagEditingVertex,
     sort_by_counts: bool,
+    brain_file: BrainFileState,
 }
 
-struct MenaceRobberViewingState {
+struct RobberBagViewingState {
     bag_key: (Vec<usize>, Option<usize>),
-    editing_vertex: MenaceEditingVertex,
+    editing_vertex: BagEditingVertex,
     sort_by_counts: bool,
+    brain_file: BrainFileState,
+}
+
+// The recorded match shown in the replay tab of the statistics panel.
+struct ReplayViewingState {
+    // The number of the shown match, None until the first match is over.
+    match_number: Option<u32>,
+    // The index of the shown turn of the match.
+    turn: usize,
+    playing: bool,
+    // The time (egui's input time) of the last turn shown while playing.
+    last_step_time: f64,
+    // The path the replay is exported to, and the result of the last export.
+    path: String,
+    message: Option<String>,
+}
+
+// A recorded match with the game it was played in, exported as JSON.
+// Replaying the game with the same seed plays the same matches, unless a human played.
+#[derive(serde::Serialize)]
+struct Replay<'a> {
+    graph: &'a Graph,
+    seed: u64,
+    number_of_cops: u8,
+    number_of_steps: u8,
+    cop: &'a str,
+    robber: &'a str,
+    cop_settings: &'a StrategySettings,
+    robber_settings: &'a StrategySettings,
+    record: &'a MatchRecord,
+}
+
+// The statistics of a game, exported as JSON.
+#[derive(serde::Serialize)]
+struct GameStatistics<'a> {
+    graph: &'a Graph,
+    seed: u64,
+    number_of_cops: u8,
+    number_of_steps: u8,
+    cop: &'a str,
+    robber: &'a str,
+    cop_settings: &'a StrategySettings,
+    robber_settings: &'a StrategySettings,
+    // The number of matches won by the cops and the robber.
+    score: [u32; 2],
+    matches: &'a [MatchResult],
+    cop_scores: Vec<CopScore>,
+}
+
+// The number of matches the cops won out of the first matches, a point of the cop wins graph.
+#[derive(serde::Serialize)]
+struct CopScore {
+    matches: u32,
+    cop_wins: u32,
 }
 
 #[derive(PartialEq)]
@@ -40,10 +156,16 @@ enum GameStatisticsView {
     Graph,
     Robber,
     Cop,
+    Replay,
 }
 
 struct GameViewState {
     game: Game,
+    // The registry names of the cop and robber algorithms, and their settings.
+    cop_name: String,
+    robber_name: String,
+    cop_settings: StrategySettings,
+    robber_settings: StrategySettings,
     // Fields for animating moves.
     previous_cop_positions: Option<Vec<usize>>,
     previous_robber_position: Option<usize>,
@@ -54,9 +176,29 @@ struct GameViewState {
     animation_bool: bool,
     flip_animation_bool: bool,
     cop_scores: Vec<u32>,
+    // The results of all the matches played so far.
+    match_results: Vec<MatchResult>,
+    // The path the statistics are exported to, and the result of the last export.
+    statistics_path: String,
+    statistics_message: Option<String>,
     game_statistics_view: GameStatisticsView,
-    menace_cop_viewing_state: Option<MenaceCopViewingState>,
-    menace_robber_viewing_state: Option<MenaceRobberViewingState>,
+    cop_bag_viewing_state: Option<CopBagViewingState>,
+    robber_bag_viewing_state: Option<RobberBagViewingState>,
+    replay_viewing_state: ReplayViewingState,
+    // The positions the user has chosen so far for human cops. Each cop is moved in turn.
+    human_cop_positions: Vec<usize>,
+    // Set by the game thread once it waits for the move of a human player,
+    // so the previous move is animated before the user is asked to move.
+    waiting_for_human: bool,
+    // While paused, the game thread only plays the single updates the user asks for
+    // by setting step_requested, which the thread resets.
+    paused: bool,
+    step_requested: bool,
+    // A multiple of the normal speed, which scales the time between updates and the animations.
+    speed: f32,
+    // What the game thread is doing while it chooses a slow player's move without holding
+    // the lock, if it is.
+    thinking: Option<&'static str>,
 }
 
 impl GameViewState {
@@ -64,31 +206,40 @@ impl GameViewState {
         graph: &Graph,
         number_of_cops: u8,
         number_of_steps: u8,
-        cop: Algorithm,
-        robber: Algorithm,
+        (cop_name, cop, cop_settings): (&str, Box<dyn CopStrategy>, StrategySettings),
+        (robber_name, robber, robber_settings): (&str, Box<dyn RobberStrategy>, StrategySettings),
+        seed: u64,
     ) -> Self {
-        let menace_cop_viewing_state = if cop == Algorithm::Menace {
-            Some(MenaceCopViewingState {
-                bag_key: None,
-                editing_vertex: MenaceEditingVertex::None,
-                sort_by_counts: false,
-            })
-        } else {
-            None
-        };
-
-        let menace_robber_viewing_state = if robber == Algorithm::Menace {
-            Some(MenaceRobberViewingState {
-                bag_key: (vec![0; number_of_cops as usize], None),
-                editing_vertex: MenaceEditingVertex::None,
-                sort_by_counts: false,
-            })
-        } else {
-            None
-        };
+        let cop_bag_viewing_state =
+            if cop.as_any().is::<MenaceCop>() || cop.as_any().is::<QLearningCop>() {
+                Some(CopBagViewingState {
+                    bag_key: None,
+                    editing_vertex: BagEditingVertex::None,
+                    sort_by_counts: false,
+                    brain_file: BrainFileState::new("menace_cop.json"),
+                })
+            } else {
+                None
+            };
+
+        let robber_bag_viewing_state =
+            if robber.as_any().is::<MenaceRobber>() || robber.as_any().is::<QLearningRobber>() {
+                Some(RobberBagViewingState {
+                    bag_key: (vec![0; number_of_cops as usize], None),
+                    editing_vertex: BagEditingVertex::None,
+                    sort_by_counts: false,
+                    brain_file: BrainFileState::new("menace_robber.json"),
+                })
+            } else {
+                None
+            };
 
         Self {
-            game: Game::new(graph, number_of_cops, number_of_steps, cop, robber),
+            game: Game::new(graph, number_of_steps, cop, robber, seed),
+            cop_name: cop_name.to_string(),
+            robber_name: robber_name.to_string(),
+            cop_settings,
+            robber_settings,
             previous_cop_positions: None,
             previous_robber_position: None,
             animation_bool: false,
@@ -97,17 +248,88 @@ impl GameViewState {
             flip_animation_bool: true,
             game_statistics_view: GameStatisticsView::Cop,
             cop_scores: vec![],
-            menace_cop_viewing_state,
-            menace_robber_viewing_state,
+            match_results: vec![],
+            statistics_path: "statistics".to_string(),
+            statistics_message: None,
+            cop_bag_viewing_state,
+            robber_bag_viewing_state,
+            replay_viewing_state: ReplayViewingState {
+                match_number: None,
+                turn: 0,
+                playing: false,
+                last_step_time: 0.0,
+                path: "replay.json".to_string(),
+                message: None,
+            },
+            human_cop_positions: vec![],
+            waiting_for_human: false,
+            paused: false,
+            step_requested: false,
+            speed: 1.0,
+            thinking: None,
+        }
+    }
+
+    // Whether it's the turn of a human player who hasn't chosen their move yet.
+    fn is_waiting_for_human(&self) -> bool {
+        match self.game.turn {
+            Turn::Cop => self
+                .game
+                .cop
+                .as_any()
+                .downcast_ref::<HumanCop>()
+                .map_or(false, |cop| !cop.has_next_move()),
+            Turn::Robber => self
+                .game
+                .robber
+                .as_any()
+                .downcast_ref::<HumanRobber>()
+                .map_or(false, |robber| !robber.has_next_move()),
+            Turn::Over => false,
         }
     }
 
     fn update(&mut self) {
+        self.play(Game::update);
+    }
+
+    // Takes the player whose turn it is out of the game if its move can take long, so the
+    // game thread can choose the move without holding the lock. Otherwise plays the update.
+    fn start_update(&mut self) -> Option<Mover> {
+        if !self.game.next_move_is_slow() {
+            self.update();
+            return None;
+        }
+        // The optimal players are only slow until they have solved the game.
+        let solving = match self.game.turn {
+            Turn::Cop => self.game.cop.as_any().is::<OptimalCop>(),
+            Turn::Robber => self.game.robber.as_any().is::<OptimalRobber>(),
+            Turn::Over => false,
+        };
+        self.thinking = Some(if solving {
+            "Solving the game…"
+        } else {
+            "Thinking…"
+        });
+        self.game.take_mover()
+    }
+
+    // Plays the move chosen by a mover taken by start_update.
+    fn finish_update(&mut self, chosen_move: ChosenMove) {
+        self.thinking = None;
+        self.play(|game| game.play_move(chosen_move));
+    }
+
+    // Plays an update of the game, animating the move and updating the statistics.
+    fn play(&mut self, update: impl FnOnce(&mut Game)) {
         self.previous_cop_positions = self.game.cop_positions.clone();
         self.previous_robber_position = self.game.robber_position;
         self.flip_animation_bool = true;
-        self.game.update();
+        update(&mut self.game);
         if self.game.turn == Turn::Over {
+            if let Some(record) = self.game.records.back() {
+                self.match_results.push(record.result());
+            }
             let number_of_turns = self.game.score[0] + self.game.score[1];
             // If the number_of_turns is a square.
             if ((number_of_turns as f64).sqrt() as u32).pow(2) == number_of_turns {
@@ -115,19 +337,74 @@ impl GameViewState {
             }
         }
     }
+
+    // Whether the cops won the match, if it's over.
+    fn match_result(&self) -> Option<bool> {
+        let Game {
+            turn,
+            cop_positions,
+            robber_position,
+            ..
+        } = &self.game;
+        (*turn == Turn::Over).then(|| {
+            cop_positions
+                .as_ref()
+                .zip(*robber_position)
+                .map_or(false, |(cop_positions, robber_position)| {
+                    cop_positions.contains(&robber_position)
+                })
+        })
+    }
+}
+
+// Plays the next update of the game, choosing a slow player's move without holding the lock.
+// Returns None if there is no game anymore, and otherwise whether the cops won the match
+// if the update ended it.
+fn play_update(game_view_state: &Mutex<Option<GameViewState>>) -> Option<Option<bool>> {
+    let mover = {
+        let mut game_view_state = game_view_state.lock();
+        let game_view_state = game_view_state.as_mut()?;
+        match game_view_state.start_update() {
+            Some(mover) => mover,
+            None => return Some(game_view_state.match_result()),
+        }
+    };
+    let chosen_move = mover.choose();
+    let mut game_view_state = game_view_state.lock();
+    let game_view_state = game_view_state.as_mut()?;
+    game_view_state.finish_update(chosen_move);
+    Some(game_view_state.match_result())
+}
+
+// A batch of games which the game thread plays as fast as it can, without animations.
+#[derive(Clone, Copy)]
+struct Batch {
+    number_of_games: u32,
+    games_played: u32,
+    // The number of games of the batch won by the cops.
+    cop_wins: u32,
+    // Set by the user to stop the batch early.
+    cancelled: bool,
+}
+
+impl Batch {
+    fn is_over(&self) -> bool {
+        self.cancelled || self.games_played >= self.number_of_games
+    }
 }
 
 // GameHandle is a handle to a new thread created to play the game.
-// It also allows us to request the new thread to perform multiple updates at a time.
+// It also allows us to request the new thread to play a batch of games.
 pub struct GameHandle {
     // The game view state. We wrap GameViewState in an option
     // so that we can set game to hold None when we want the new thread to stop.
     game_view_state: Arc<Mutex<Option<GameViewState>>>,
-    // The number of games we want to compute immediately.
-    number_of_immediate_games: Arc<Mutex<Option<u32>>>,
-    // Handle of the new thread. We store it in an Option so that we can take it out of GameHandle
-    // and call join on it to wait for the new thread to finish.
-    thread_handle: Option<JoinHandle<()>>,
+    // The batch of games being played as fast as possible, if any.
+    batch: Arc<Mutex<Option<Batch>>>,
+    // The number of games of the next batch.
+    batch_size: u32,
+    // Whether the user plays one of the sides, in which case we can't play games immediately.
+    has_human_player: bool,
 }
 
 impl GameHandle {
@@ -135,77 +412,111 @@ impl GameHandle {
         graph: &Graph,
         number_of_cops: u8,
         number_of_steps: u8,
-        cop: Algorithm,
-        robber: Algorithm,
+        cop: (&str, Box<dyn CopStrategy>, StrategySettings),
+        robber: (&str, Box<dyn RobberStrategy>, StrategySettings),
+        seed: u64,
         ctx: egui::Context,
     ) -> Self {
+        let has_human_player =
+            cop.1.as_any().is::<HumanCop>() || robber.1.as_any().is::<HumanRobber>();
         let game_and_animation_state = Arc::new(Mutex::new(Some(GameViewState::new(
             graph,
             number_of_cops,
             number_of_steps,
             cop,
             robber,
+            seed,
         ))));
         let game_and_animation_state_clone = Arc::clone(&game_and_animation_state);
 
-        let number_of_immediate_games = Arc::new(Mutex::new(None));
-        let number_of_immediate_games_clone = Arc::clone(&number_of_immediate_games);
+        let batch = Arc::new(Mutex::new(None::<Batch>));
+        let batch_clone = Arc::clone(&batch);
 
-        let handle = thread::spawn(move || loop {
-            let mut have_done_multiple_moves = false;
-
-            loop {
-                let games = *(number_of_immediate_games.lock());
-
-                if let Some(games) = games {
-                    {
-                        let mut game_and_animation_state = game_and_animation_state.lock();
-
-                        let mut games_till_now = 0;
-                        if let Some(game_and_animation_state) = &mut (*game_and_animation_state) {
-                            while games_till_now < games {
-                                game_and_animation_state.update();
-                                if game_and_animation_state.game.turn == Turn::Over {
-                                    games_till_now += 1;
-                                }
-                            }
-                            game_and_animation_state.update();
-                        } else {
-                            return; // There is no game, so we return.
-                        }
+        thread::spawn(move || loop {
+            // We play batches one update at a time, so the game is only locked for short times
+            // and the UI can show the statistics and cancel the batch while it's played.
+            let batch_is_over = batch.lock().as_ref().map(Batch::is_over);
+            if batch_is_over == Some(false) {
+                let Some(match_result) = play_update(&game_and_animation_state) else {
+                    return; // There is no game, so we return.
+                };
+                if let (Some(cop_won), Some(batch)) = (match_result, &mut (*batch.lock())) {
+                    batch.games_played += 1;
+                    if cop_won {
+                        batch.cop_wins += 1;
                     }
+                }
+                continue;
+            }
+            if batch_is_over == Some(true) {
+                *batch.lock() = None;
+                ctx.request_repaint();
+            }
 
-                    let mut number_of_immediate_games = number_of_immediate_games.lock();
-                    // We can unwrap as we're the only one that can decrement the count,
-                    // and since we're here, the count is non zero.
-                    let remaining_games = (*number_of_immediate_games).unwrap() - games;
-                    *number_of_immediate_games = if remaining_games > 0 {
-                        Some(remaining_games)
-                    } else {
-                        None
-                    };
-
-                    have_done_multiple_moves = true;
-                } else if have_done_multiple_moves {
-                    break;
-                } else {
-                    let mut game_and_animation_state = game_and_animation_state.lock();
-                    if let Some(game_and_animation_state) = &mut (*game_and_animation_state) {
-                        game_and_animation_state.update();
-                    } else {
-                        return; // There is no game, so we return.
-                    }
-                    break;
+            let is_waiting_for_human;
+            let is_paused;
+            let mut mover = None;
+            {
+                let mut game_and_animation_state = game_and_animation_state.lock();
+                let Some(game_and_animation_state) = &mut (*game_and_animation_state) else {
+                    return; // There is no game, so we return.
+                };
+                // We don't play a human's turn until the user has chosen their move.
+                is_waiting_for_human = game_and_animation_state.is_waiting_for_human();
+                if is_waiting_for_human && !game_and_animation_state.waiting_for_human {
+                    ctx.request_repaint();
+                }
+                game_and_animation_state.waiting_for_human = is_waiting_for_human;
+                is_paused =
+                    game_and_animation_state.paused && !game_and_animation_state.step_requested;
+                if !is_waiting_for_human && !is_paused {
+                    game_and_animation_state.step_requested = false;
+                    mover = game_and_animation_state.start_update();
                 }
             }
+            if let Some(mover) = mover {
+                // We choose a slow move without the lock, so the game is shown meanwhile.
+                ctx.request_repaint();
+                let chosen_move = mover.choose();
+                let mut game_and_animation_state = game_and_animation_state.lock();
+                let Some(game_and_animation_state) = &mut (*game_and_animation_state) else {
+                    return; // There is no game, so we return.
+                };
+                game_and_animation_state.finish_update(chosen_move);
+            }
 
+            if is_waiting_for_human || is_paused {
+                // We poll often, so the game continues as soon as the user has moved or stepped.
+                thread::sleep(POLL_TIME);
+                continue;
+            }
             ctx.request_repaint();
-            thread::sleep(Duration::from_secs_f32(2.0 * ANIMATION_TIME));
+            // We wait in short sleeps, so a change of the speed, a step or a batch
+            // takes effect right away.
+            let mut waiting_time = Duration::ZERO;
+            loop {
+                thread::sleep(POLL_TIME);
+                waiting_time += POLL_TIME;
+                if batch.lock().is_some() {
+                    break;
+                }
+                let game_and_animation_state = game_and_animation_state.lock();
+                let Some(game_and_animation_state) = &(*game_and_animation_state) else {
+                    return; // There is no game, so we return.
+                };
+                if waiting_time.as_secs_f32()
+                    >= 2.0 * ANIMATION_TIME / game_and_animation_state.speed
+                    || game_and_animation_state.step_requested
+                {
+                    break;
+                }
+            }
         });
         GameHandle {
             game_view_state: game_and_animation_state_clone,
-            number_of_immediate_games: number_of_immediate_games_clone,
-            thread_handle: Some(handle),
+            batch: batch_clone,
+            batch_size: 1000,
+            has_human_player,
         }
     }
 }
@@ -213,12 +524,207 @@ impl GameHandle {
 impl Drop for GameHandle {
     fn drop(&mut self) {
         // Set game_and_animation_state to None to inform the new thread
-        // to stop playing the game.
+        // to stop playing the game. We don't wait for the thread, as it may be choosing
+        // a slow move, which it drops once it sees there is no game.
         *(self.game_view_state.lock()) = None;
+    }
+}
+
+// The result of a computation on a new thread. The computation is told to stop when the job
+// is dropped, e.g. because the user has selected another graph.
+struct BackgroundJob<T> {
+    // None until the new thread has finished.
+    result: Arc<Mutex<Option<T>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T: Clone + Send + Sync + 'static> BackgroundJob<T> {
+    // compute is given a function which returns whether the job has been cancelled,
+    // and returns None if it stopped because of it.
+    fn new(
+        ctx: egui::Context,
+        compute: impl FnOnce(&dyn Fn() -> bool) -> Option<T> + Send + 'static,
+    ) -> Self {
+        let result = Arc::new(Mutex::new(None));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let thread_result = Arc::clone(&result);
+        let thread_cancelled = Arc::clone(&cancelled);
+        // We don't wait for the thread when the job is dropped, so the UI doesn't freeze.
+        thread::spawn(move || {
+            if let Some(value) = compute(&|| thread_cancelled.load(AtomicOrdering::Relaxed)) {
+                *thread_result.lock() = Some(value);
+                ctx.request_repaint();
+            }
+        });
+
+        BackgroundJob { result, cancelled }
+    }
+
+    fn result(&self) -> Option<T> {
+        self.result.lock().clone()
+    }
+}
+
+impl<T> Drop for BackgroundJob<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, AtomicOrdering::Relaxed);
+    }
+}
+
+// GraphAnalysisHandle is a handle to the new threads created to analyse a graph, as solving
+// the game for several numbers of cops can take seconds.
+pub struct GraphAnalysisHandle {
+    // The graph and number of cops being analysed, so we can analyse again when they change.
+    graph: Graph,
+    number_of_cops: u8,
+    // Whether the graph is dismantlable and its cop number, which we keep when only the number
+    // of cops changes.
+    cop_number: BackgroundJob<(bool, CopNumber)>,
+    capture_time: BackgroundJob<CaptureTime>,
+}
+
+impl GraphAnalysisHandle {
+    fn new(graph: &Graph, number_of_cops: u8, ctx: egui::Context) -> Self {
+        let thread_graph = graph.clone();
+        let cop_number = BackgroundJob::new(ctx.clone(), move |cancelled| {
+            let cop_number = analysis::cop_number(&thread_graph, MAX_NUMBER_OF_COPS, cancelled)?;
+            Some((analysis::is_dismantlable(&thread_graph), cop_number))
+        });
+
+        GraphAnalysisHandle {
+            graph: graph.clone(),
+            number_of_cops,
+            cop_number,
+            capture_time: Self::capture_time(graph, number_of_cops, ctx),
+        }
+    }
+
+    // Analyses the graph for another number of cops, which only changes the capture time.
+    fn set_number_of_cops(&mut self, number_of_cops: u8, ctx: egui::Context) {
+        self.number_of_cops = number_of_cops;
+        self.capture_time = Self::capture_time(&self.graph, number_of_cops, ctx);
+    }
+
+    fn capture_time(
+        graph: &Graph,
+        number_of_cops: u8,
+        ctx: egui::Context,
+    ) -> BackgroundJob<CaptureTime> {
+        let graph = graph.clone();
+        BackgroundJob::new(ctx, move |cancelled| {
+            (!cancelled()).then(|| analysis::capture_time(&graph, number_of_cops))
+        })
+    }
+}
+
+// TournamentHandle is a handle to a new thread created to play a tournament.
+pub struct TournamentHandle {
+    tournament: Tournament,
+    // The results so far, updated by the new thread after every pairing.
+    results: Arc<Mutex<TournamentResults>>,
+    // The error which stopped the tournament, if any.
+    error: Arc<Mutex<Option<String>>>,
+    // Set to stop the new thread before the tournament is over.
+    stop: Arc<AtomicBool>,
+    // Only used to check whether the tournament is over: we don't wait for the new thread when
+    // the handle is dropped, so the UI doesn't freeze until the current match is over.
+    thread_handle: JoinHandle<()>,
+}
+
+impl TournamentHandle {
+    fn new(tournament: Tournament, registry: StrategyRegistry, ctx: egui::Context) -> Self {
+        let results = Arc::new(Mutex::new(TournamentResults::new(&tournament)));
+        let error = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_tournament = tournament.clone();
+        let thread_results = Arc::clone(&results);
+        let thread_error = Arc::clone(&error);
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let result = thread_tournament.play(
+                &registry,
+                |graph, cop, robber, cop_wins| {
+                    thread_results
+                        .lock()
+                        .set_cop_wins(graph, cop, robber, cop_wins);
+                    ctx.request_repaint();
+                },
+                || thread_stop.load(AtomicOrdering::Relaxed),
+            );
+            if let Err(message) = result {
+                *thread_error.lock() = Some(message);
+            }
+            ctx.request_repaint();
+        });
+
+        TournamentHandle {
+            tournament,
+            results,
+            error,
+            stop,
+            thread_handle: handle,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.thread_handle.is_finished()
+    }
+}
+
+impl Drop for TournamentHandle {
+    // The new thread stops before its next match and exits on its own.
+    fn drop(&mut self) {
+        self.stop.store(true, AtomicOrdering::Relaxed);
+    }
+}
+
+pub struct TournamentViewState {
+    // Whether each graph plays in the tournament, in the order of the app's graphs.
+    selected_graphs: Vec<bool>,
+    // The algorithms which can play and whether they're selected. Human players can't play.
+    cops: Vec<(String, bool)>,
+    robbers: Vec<(String, bool)>,
+    number_of_matches: u32,
+    reset_learners: bool,
+    handle: Option<TournamentHandle>,
+}
+
+impl TournamentViewState {
+    fn new(
+        graphs: &[Graph],
+        current_graph: usize,
+        registry: &StrategyRegistry,
+        number_of_cops: u8,
+    ) -> Self {
+        let settings = StrategySettings::default();
+        let cops = registry
+            .cop_names()
+            .filter(|name| {
+                registry
+                    .create_cop(name, number_of_cops, &settings)
+                    .map_or(false, |cop| !cop.as_any().is::<HumanCop>())
+            })
+            .map(|name| (name.to_string(), true))
+            .collect();
+        let robbers = registry
+            .robber_names()
+            .filter(|name| {
+                registry
+                    .create_robber(name, number_of_cops, &settings)
+                    .map_or(false, |robber| !robber.as_any().is::<HumanRobber>())
+            })
+            .map(|name| (name.to_string(), true))
+            .collect();
 
-        let thread_handle = self.thread_handle.take();
-        if let Some(thread_handle) = thread_handle {
-            thread_handle.join().unwrap();
+        Self {
+            selected_graphs: (0..graphs.len()).map(|i| i == current_graph).collect(),
+            cops,
+            robbers,
+            number_of_matches: 100,
+            reset_learners: true,
+            handle: None,
         }
     }
 }
@@ -243,12 +749,58 @@ pub struct GraphCreationState {
     mode: Mode,
     selected_item: SelectedItem,
     graph: Graph,
+    // The index of the graph we're editing, or None if we're creating a new graph.
+    edited_graph: Option<usize>,
+}
+
+// The graph selected in the graph manager.
+pub struct GraphManagerState {
+    selected_graph: usize,
+    // The name the selected graph is renamed to.
+    name: String,
+    // Whether we're asking the user to confirm deleting the selected graph.
+    confirm_delete: bool,
+}
+
+impl GraphManagerState {
+    fn new(graphs: &[Graph], selected_graph: usize) -> Self {
+        Self {
+            selected_graph,
+            name: graphs[selected_graph].name.clone(),
+            confirm_delete: false,
+        }
+    }
+}
+
+// The graph family and parameters chosen in the graph generator.
+pub struct GraphGenerationState {
+    generator: GraphGenerator,
+    // Whether we only keep the largest connected component of random graphs.
+    largest_component: bool,
+    // The generated graph with the generator and largest_component it was generated with,
+    // so we only generate it again when they change.
+    preview: Option<(GraphGenerator, bool, Result<Graph, String>)>,
+}
+
+// The state of importing a graph from a file, or exporting the selected graph.
+pub struct GraphFileState {
+    path: String,
+    format: GraphFormat,
+    // The result of the last export/import.
+    message: Option<String>,
+    pending_file: Option<PendingFile>,
+    // The imported graph, shown until the user adds it.
+    imported_graph: Option<Graph>,
 }
 
 pub enum View {
     GameSettingsSelection,
     GraphCreation(GraphCreationState),
+    GraphGeneration(GraphGenerationState),
+    GraphFile(GraphFileState),
+    GraphManager(GraphManagerState),
     Game(GameHandle),
+    Tournament(Box<TournamentViewState>),
 }
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -259,8 +811,21 @@ pub struct TemplateApp {
     current_graph: usize,
     number_of_cops: u8,
     number_of_steps: u8,
-    cop: Algorithm,
-    robber: Algorithm,
+    // The names of the selected algorithms in the strategy registry.
+    cop_algorithm: String,
+    robber_algorithm: String,
+    // The parameters of the cop and robber algorithms.
+    cop_settings: StrategySettings,
+    robber_settings: StrategySettings,
+    seed: u64,
+    // Whether we choose a new seed every time a game is created.
+    random_seed: bool,
+    #[serde(skip)]
+    registry: StrategyRegistry,
+    // The analysis of the selected graph for the selected number of cops.
+    // We keep the graph and number of cops it was computed for, so we can recompute it when they change.
+    #[serde(skip)]
+    graph_analysis: Option<GraphAnalysisHandle>,
     #[serde(skip)]
     view: View,
 }
@@ -272,8 +837,14 @@ impl Default for TemplateApp {
             current_graph: 0,
             number_of_cops: 1,
             number_of_steps: 1,
-            cop: Algorithm::Random,
-            robber: Algorithm::Random,
+            cop_algorithm: "Random".to_string(),
+            robber_algorithm: "Random".to_string(),
+            cop_settings: StrategySettings::default(),
+            robber_settings: StrategySettings::default(),
+            seed: 0,
+            random_seed: true,
+            registry: StrategyRegistry::default(),
+            graph_analysis: None,
             view: View::GameSettingsSelection,
         }
     }
@@ -330,6 +901,7 @@ fn show_graph_editor(
         },
         selected_item,
         mode,
+        ..
     } = graph_creation_state;
 
     let (mut response, painter) = ui.allocate_painter(Vec2::new(SIZE, SIZE), Sense::click());
@@ -508,14 +1080,69 @@ fn show_graph_editor(
     response
 }
 
-fn show_game(ui: &mut egui::Ui, graph: &Graph, game_state: &mut GameViewState) -> egui::Response {
+fn show_score(ui: &mut egui::Ui, game: &Game) {
     ui.horizontal(|ui| {
         ui.label(RichText::new("Score (Cop-Robber):").strong());
-        ui.label(format!(
-            "{} - {}",
-            game_state.game.score[0], game_state.game.score[1],
-        ));
+        ui.label(format!("{} - {}", game.score[0], game.score[1]));
+        ui.label(RichText::new("Seed:").strong());
+        ui.label(game.seed.to_string());
+    });
+}
+
+// Shows the progress of a batch of games, with a button to cancel it which calls cancel.
+fn show_batch_progress(ui: &mut egui::Ui, batch: &Batch, cancel: impl FnOnce()) {
+    let progress = batch.games_played as f32 / batch.number_of_games as f32;
+    ui.add(egui::ProgressBar::new(progress).text(format!(
+        "{} of {} games played, {} remaining",
+        batch.games_played,
+        batch.number_of_games,
+        batch.number_of_games - batch.games_played
+    )));
+    ui.horizontal(|ui| {
+        if batch.games_played > 0 {
+            ui.label(format!(
+                "Cop win rate in the batch: {:.3}",
+                batch.cop_wins as f64 / batch.games_played as f64
+            ));
+        }
+        if batch.cancelled {
+            ui.label("Cancelling...");
+        } else if ui.button("Cancel").clicked() {
+            cancel();
+        }
+    });
+    // The game thread doesn't ask for repaints while it plays a batch.
+    ui.ctx().request_repaint_after(BATCH_REPAINT_TIME);
+}
+
+// Shows the controls to pause and resume the game, play a single update while paused,
+// and change the speed.
+fn show_game_controls(ui: &mut egui::Ui, game_state: &mut GameViewState) {
+    ui.horizontal(|ui| {
+        if game_state.paused {
+            if ui.button("Resume").clicked() {
+                game_state.paused = false;
+            }
+            if ui.button("Step").clicked() {
+                game_state.step_requested = true;
+            }
+        } else if ui.button("Pause").clicked() {
+            game_state.paused = true;
+        }
+        ui.add(
+            egui::Slider::new(&mut game_state.speed, MIN_SPEED..=MAX_SPEED)
+                .logarithmic(true)
+                .text("Speed"),
+        );
+        if let Some(thinking) = game_state.thinking {
+            ui.spinner();
+            ui.label(thinking);
+        }
     });
+}
+
+fn show_game(ui: &mut egui::Ui, graph: &Graph, game_state: &mut GameViewState) -> egui::Response {
+    show_score(ui, &game_state.game);
 
     let size = egui::vec2(300.0, 300.0);
     let (rect, mut response) = ui.allocate_exact_size(size, egui::Sense::hover());
@@ -527,9 +1154,11 @@ fn show_game(ui: &mut egui::Ui, graph: &Graph, game_state: &mut GameViewState) -
     }
 
     if ui.is_rect_visible(rect) {
-        let mut animation_distance =
-            ui.ctx()
-                .animate_bool_with_time(response.id, game_state.animation_bool, ANIMATION_TIME);
+        let mut animation_distance = ui.ctx().animate_bool_with_time(
+            response.id,
+            game_state.animation_bool,
+            ANIMATION_TIME / game_state.speed,
+        );
         if !game_state.animation_bool {
             animation_distance = 1.0 - animation_distance;
         }
@@ -606,6 +1235,109 @@ fn show_game(ui: &mut egui::Ui, graph: &Graph, game_state: &mut GameViewState) -
     response
 }
 
+// Lets the user choose the move of a human player by clicking vertices.
+// Only the current vertex of a player or one of its neighbours is accepted.
+fn show_human_move(
+    ui: &mut egui::Ui,
+    graph: &Graph,
+    game_state: &mut GameViewState,
+    number_of_cops: u8,
+) {
+    show_score(ui, &game_state.game);
+
+    let GameViewState {
+        game,
+        human_cop_positions,
+        ..
+    } = game_state;
+
+    let allowed_moves = |vertex: usize| -> Vec<usize> {
+        graph.adjacency_list[vertex]
+            .iter()
+            .copied()
+            .chain(std::iter::once(vertex))
+            .collect()
+    };
+
+    let clicked_vertex = |ui: &mut egui::Ui, response: egui::Response| {
+        let response = ui.interact(response.rect, response.id, Sense::click());
+        response
+            .clicked()
+            .then(|| response.interact_pointer_pos())
+            .flatten()
+            .and_then(|pos| vertex_at(graph, response.rect, pos))
+    };
+
+    if game.turn == Turn::Cop {
+        // The cops which have already moved are shown at their new positions.
+        let mut cops = human_cop_positions.clone();
+        let cop = cops.len();
+        if let Some(cop_positions) = &game.cop_positions {
+            cops.extend_from_slice(&cop_positions[cop..]);
+            ui.label(format!(
+                "Move cop {}: click its vertex or a neighbour.",
+                cop + 1
+            ));
+        } else {
+            ui.label(format!("Click the starting vertex of cop {}.", cop + 1));
+        }
+
+        let response =
+            show_graph_with_cops_and_robber(ui, Some(&cops), game.robber_position, graph, 300.0);
+        if let Some(vertex) = clicked_vertex(ui, response) {
+            let allowed = match &game.cop_positions {
+                Some(cop_positions) => allowed_moves(cop_positions[cop]).contains(&vertex),
+                None => true,
+            };
+            if allowed {
+                human_cop_positions.push(vertex);
+            }
+        }
+
+        if human_cop_positions.len() == number_of_cops as usize {
+            if let Some(cop) = game.cop.as_any_mut().downcast_mut::<HumanCop>() {
+                cop.set_next_move(std::mem::take(human_cop_positions));
+            }
+        }
+    } else {
+        let label = if game.robber_position.is_some() {
+            "Move the robber: click its vertex or a neighbour."
+        } else {
+            "Click the starting vertex of the robber."
+        };
+        ui.label(label);
+
+        let response = show_graph_with_cops_and_robber(
+            ui,
+            game.cop_positions.as_deref(),
+            game.robber_position,
+            graph,
+            300.0,
+        );
+        if let Some(vertex) = clicked_vertex(ui, response) {
+            let allowed = match game.robber_position {
+                Some(robber_position) => allowed_moves(robber_position).contains(&vertex),
+                None => true,
+            };
+            if allowed {
+                if let Some(robber) = game.robber.as_any_mut().downcast_mut::<HumanRobber>() {
+                    robber.set_next_move(vertex);
+                }
+            }
+        }
+    }
+}
+
+// The vertex at pos in a graph drawn in rect, if any.
+fn vertex_at(graph: &Graph, rect: Rect, pos: Pos2) -> Option<usize> {
+    const VERTEX_RADIUS: f32 = 8.0;
+
+    graph
+        .vertices
+        .iter()
+        .position(|&vertex| rect.lerp(vertex.into()).distance(pos) <= VERTEX_RADIUS)
+}
+
 fn select_graph_vertex(
     ui: &mut egui::Ui,
     vertex: &mut usize,
@@ -734,45 +1466,268 @@ fn show_graph_with_cops_and_robber(
     response
 }
 
-fn game_settings_selection(
-    ctx: &egui::Context,
-    graphs: &Vec<Graph>,
-    current_graph: &mut usize,
-    number_of_cops: &mut u8,
-    number_of_steps: &mut u8,
-    cop: &mut Algorithm,
-    robber: &mut Algorithm,
-) -> Option<View> {
-    let mut view = None;
-
-    egui::CentralPanel::default().show(ctx, |ui| {
-        ui.heading("Cops and Robbers");
+// Shows an algorithm in the list of algorithms of a side, which can only be chosen if
+// it can play on the selected graph.
+fn show_algorithm_choice(
+    ui: &mut egui::Ui,
+    algorithm: &mut String,
+    name: &str,
+    check: Result<(), String>,
+) {
+    let response = ui.add_enabled(
+        check.is_ok(),
+        egui::SelectableLabel::new(algorithm == name, name),
+    );
+    if let Err(error) = check {
+        response.on_disabled_hover_text(error);
+    } else if response.clicked() {
+        *algorithm = name.to_string();
+    }
+}
 
+fn show_graph_analysis(ui: &mut egui::Ui, graph_analysis: &GraphAnalysisHandle) {
+    // Shows a property of the graph, with a spinner until it has been computed.
+    let show_property = |ui: &mut egui::Ui, name: String, value: Option<String>| {
         ui.horizontal(|ui| {
-            ui.label("Graph");
-            egui::ComboBox::from_id_source("Graph")
-                .selected_text(graphs[*current_graph].name.clone())
-                .show_ui(ui, |ui| {
-                    for (i, graph) in graphs.iter().enumerate() {
-                        ui.selectable_value(current_graph, i, graph.name.clone());
-                    }
-                });
-            if ui.button("New graph").clicked() {
-                view = Some(View::GraphCreation(GraphCreationState::default()));
+            ui.label(RichText::new(name).strong());
+            match value {
+                Some(value) => {
+                    ui.label(value);
+                }
+                None => {
+                    ui.spinner();
+                    ui.label("Computing…");
+                }
             }
         });
-        show_graph(ui, &graphs[*current_graph]);
+    };
+
+    ui.vertical(|ui| {
+        let cop_number = graph_analysis.cop_number.result();
+        show_property(
+            ui,
+            "Cop-win (dismantlable):".to_string(),
+            cop_number.map(|(dismantlable, _)| if dismantlable { "Yes" } else { "No" }.to_string()),
+        );
+        show_property(
+            ui,
+            "Cop number:".to_string(),
+            cop_number.map(|(_, cop_number)| match cop_number {
+                CopNumber::Exactly(cop_number) => cop_number.to_string(),
+                CopNumber::MoreThan(cops) => format!("More than {cops}"),
+                CopNumber::Unknown => "Graph too large".to_string(),
+            }),
+        );
+        show_property(
+            ui,
+            format!("Capture time ({} cops):", graph_analysis.number_of_cops),
+            graph_analysis
+                .capture_time
+                .result()
+                .map(|capture_time| match capture_time {
+                    CaptureTime::Steps(steps) => format!("{steps} steps"),
+                    CaptureTime::Never => "Never".to_string(),
+                    CaptureTime::Unknown => "Graph too large".to_string(),
+                }),
+        );
+    });
+}
 
-        ui.horizontal(|ui| {
-            ui.label("Number of cops");
-            egui::ComboBox::from_id_source("Number of cops")
-                .selected_text(format!("{number_of_cops}"))
-                .show_ui(ui, |ui| {
-                    for i in 1..=3 {
-                        ui.selectable_value(number_of_cops, i, i.to_string());
-                    }
-                });
-        });
+pub(crate) fn show_menace_settings(
+    ui: &mut egui::Ui,
+    heading: &str,
+    settings: &mut MenaceSettings,
+) {
+    egui::CollapsingHeader::new(heading).show(ui, |ui| {
+        egui::Grid::new(heading).show(ui, |ui| {
+            ui.label("Initial tokens");
+            ui.add(egui::DragValue::new(&mut settings.initial_tokens).clamp_range(1..=1000));
+            ui.end_row();
+
+            ui.label("Win reward");
+            ui.add(egui::DragValue::new(&mut settings.win_reward).clamp_range(-100..=100));
+            ui.end_row();
+
+            ui.label("Loss reward");
+            ui.add(egui::DragValue::new(&mut settings.loss_reward).clamp_range(-100..=100));
+            ui.end_row();
+
+            let mut has_draw_reward = settings.draw_reward.is_some();
+            ui.checkbox(&mut has_draw_reward, "Draw reward")
+                .on_hover_text("Reward a robber escape until the steps run out as a draw");
+            match (has_draw_reward, &mut settings.draw_reward) {
+                (true, Some(draw_reward)) => {
+                    ui.add(egui::DragValue::new(draw_reward).clamp_range(-100..=100));
+                }
+                (true, None) => settings.draw_reward = Some(0),
+                (false, _) => settings.draw_reward = None,
+            }
+            ui.end_row();
+
+            ui.checkbox(&mut settings.scale_by_closeness, "Scale by closeness")
+                .on_hover_text("Give moves closer to the end of the match a larger reward");
+            ui.end_row();
+
+            ui.checkbox(&mut settings.use_symmetries, "Use symmetries")
+                .on_hover_text(
+                    "Positions which are equivalent under a symmetry of the graph share a bag",
+                );
+            ui.end_row();
+        });
+    });
+}
+
+pub(crate) fn show_qlearning_settings(
+    ui: &mut egui::Ui,
+    heading: &str,
+    settings: &mut QLearningSettings,
+) {
+    egui::CollapsingHeader::new(heading).show(ui, |ui| {
+        egui::Grid::new(heading).show(ui, |ui| {
+            ui.label("Learning rate");
+            ui.add(egui::Slider::new(&mut settings.learning_rate, 0.0..=1.0));
+            ui.end_row();
+
+            ui.label("Discount");
+            ui.add(egui::Slider::new(&mut settings.discount, 0.0..=1.0));
+            ui.end_row();
+
+            ui.label("Epsilon")
+                .on_hover_text("The probability of playing a random move instead of the best one");
+            ui.add(egui::Slider::new(&mut settings.epsilon, 0.0..=1.0));
+            ui.end_row();
+
+            ui.checkbox(&mut settings.use_symmetries, "Use symmetries")
+                .on_hover_text(
+                    "Positions which are equivalent under a symmetry of the graph share a Q-table",
+                );
+            ui.end_row();
+        });
+    });
+}
+
+pub(crate) fn show_mcts_settings(ui: &mut egui::Ui, heading: &str, settings: &mut MctsSettings) {
+    egui::CollapsingHeader::new(heading).show(ui, |ui| {
+        egui::Grid::new(heading).show(ui, |ui| {
+            ui.label("Rollouts")
+                .on_hover_text("The number of simulated matches for every move");
+            ui.add(egui::DragValue::new(&mut settings.rollouts).clamp_range(1..=100_000));
+            ui.end_row();
+
+            ui.label("Horizon")
+                .on_hover_text("The number of robber moves the simulated matches last at most");
+            ui.add(egui::DragValue::new(&mut settings.horizon).clamp_range(1..=100));
+            ui.end_row();
+        });
+    });
+}
+
+pub(crate) fn show_evasive_settings(ui: &mut egui::Ui, settings: &mut EvasiveSettings) {
+    ui.horizontal(|ui| {
+        ui.label("Tie breaking");
+        egui::ComboBox::from_id_source("Evasive tie breaking")
+            .selected_text(settings.tie_breaking.name())
+            .show_ui(ui, |ui| {
+                for tie_breaking in TieBreaking::ALL {
+                    ui.selectable_value(
+                        &mut settings.tie_breaking,
+                        tie_breaking,
+                        tie_breaking.name(),
+                    );
+                }
+            });
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn game_settings_selection(
+    ctx: &egui::Context,
+    graphs: &[Graph],
+    current_graph: &mut usize,
+    number_of_cops: &mut u8,
+    number_of_steps: &mut u8,
+    cop: &mut String,
+    robber: &mut String,
+    cop_settings: &mut StrategySettings,
+    robber_settings: &mut StrategySettings,
+    seed: &mut u64,
+    random_seed: &mut bool,
+    registry: &StrategyRegistry,
+    graph_analysis: &mut Option<GraphAnalysisHandle>,
+) -> Option<View> {
+    let mut view = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Cops and Robbers");
+
+        ui.horizontal(|ui| {
+            ui.label("Graph");
+            egui::ComboBox::from_id_source("Graph")
+                .selected_text(graphs[*current_graph].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, graph) in graphs.iter().enumerate() {
+                        ui.selectable_value(current_graph, i, graph.name.clone());
+                    }
+                });
+            if ui.button("New graph").clicked() {
+                view = Some(View::GraphCreation(GraphCreationState::default()));
+            }
+            if ui.button("Generate graph").clicked() {
+                view = Some(View::GraphGeneration(GraphGenerationState {
+                    generator: GraphGenerator::families()[0],
+                    largest_component: true,
+                    preview: None,
+                }));
+            }
+            if ui.button("Import/export").clicked() {
+                view = Some(View::GraphFile(GraphFileState {
+                    path: "graph.json".to_string(),
+                    format: GraphFormat::Json,
+                    message: None,
+                    pending_file: None,
+                    imported_graph: None,
+                }));
+            }
+            if ui.button("Manage graphs").clicked() {
+                view = Some(View::GraphManager(GraphManagerState::new(
+                    graphs,
+                    *current_graph,
+                )));
+            }
+        });
+        let graph = &graphs[*current_graph];
+        match graph_analysis {
+            Some(graph_analysis) if graph_analysis.graph == *graph => {
+                if graph_analysis.number_of_cops != *number_of_cops {
+                    graph_analysis.set_number_of_cops(*number_of_cops, ctx.clone());
+                }
+            }
+            _ => {
+                *graph_analysis = Some(GraphAnalysisHandle::new(
+                    graph,
+                    *number_of_cops,
+                    ctx.clone(),
+                ));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            show_graph(ui, graph);
+            if let Some(graph_analysis) = graph_analysis {
+                show_graph_analysis(ui, graph_analysis);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Number of cops");
+            egui::ComboBox::from_id_source("Number of cops")
+                .selected_text(format!("{number_of_cops}"))
+                .show_ui(ui, |ui| {
+                    for i in 1..=MAX_NUMBER_OF_COPS {
+                        ui.selectable_value(number_of_cops, i, i.to_string());
+                    }
+                });
+        });
 
         ui.horizontal(|ui| {
             ui.label("Number of steps");
@@ -782,32 +1737,80 @@ fn game_settings_selection(
         ui.horizontal(|ui| {
             ui.label("Cop algorithm");
             egui::ComboBox::from_id_source("Cop algorithm")
-                .selected_text(format!("{cop:?}"))
+                .selected_text(cop.as_str())
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(cop, Algorithm::Random, "Random");
-                    ui.selectable_value(cop, Algorithm::Menace, "Menace");
+                    for name in registry.cop_names() {
+                        let check = registry.check_cop(name, graph, *number_of_cops);
+                        show_algorithm_choice(ui, cop, name, check);
+                    }
                 });
         });
+        registry.show_cop_settings(cop, ui, cop_settings);
 
         ui.horizontal(|ui| {
             ui.label("Robber algorithm");
             egui::ComboBox::from_id_source("Robber algorithm")
-                .selected_text(format!("{robber:?}"))
+                .selected_text(robber.as_str())
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(robber, Algorithm::Random, "Random");
-                    ui.selectable_value(robber, Algorithm::Menace, "Menace");
+                    for name in registry.robber_names() {
+                        let check = registry.check_robber(name, graph, *number_of_cops);
+                        show_algorithm_choice(ui, robber, name, check);
+                    }
                 });
         });
+        registry.show_robber_settings(robber, ui, robber_settings);
+
+        ui.horizontal(|ui| {
+            ui.label("Seed");
+            ui.add_enabled_ui(!*random_seed, |ui| seed_edit(ui, seed));
+            ui.checkbox(random_seed, "Random seed");
+        });
+
+        // The selected algorithms may not be in the registry (for example, if they were
+        // restored from a previous session) or may not be able to play on the graph,
+        // in which case we don't allow playing.
+        // We only create the strategies when the game starts, not every frame, as registered
+        // strategies may be expensive to create.
+        let check = registry
+            .check_cop(cop, graph, *number_of_cops)
+            .and(registry.check_robber(robber, graph, *number_of_cops));
+        match check {
+            Ok(()) => {
+                if ui.button("Play").clicked() {
+                    if *random_seed {
+                        *seed = rand::random();
+                    }
+                    let cop_strategy = registry.create_cop(cop, *number_of_cops, cop_settings);
+                    let robber_strategy =
+                        registry.create_robber(robber, *number_of_cops, robber_settings);
+                    // Both names are registered, so we can unwrap.
+                    view = Some(View::Game(GameHandle::new(
+                        graph,
+                        *number_of_cops,
+                        *number_of_steps,
+                        (cop, cop_strategy.unwrap(), cop_settings.clone()),
+                        (robber, robber_strategy.unwrap(), robber_settings.clone()),
+                        *seed,
+                        ctx.clone(),
+                    )));
+                }
+            }
+            Err(error) => {
+                ui.label(error);
+            }
+        }
 
-        if ui.button("Play").clicked() {
-            view = Some(View::Game(GameHandle::new(
-                &graphs[*current_graph],
+        if ui
+            .button("Tournament")
+            .on_hover_text("Play every cop algorithm against every robber algorithm")
+            .clicked()
+        {
+            view = Some(View::Tournament(Box::new(TournamentViewState::new(
+                graphs,
+                *current_graph,
+                registry,
                 *number_of_cops,
-                *number_of_steps,
-                *cop,
-                *robber,
-                ctx.clone(),
-            )));
+            ))));
         }
     });
 
@@ -835,90 +1838,1163 @@ fn graph_creation(
             );
         });
 
-        ui.add_space(5.0);
+        ui.add_space(5.0);
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut graph_creation_state.mode, Mode::Vertex, "Vertex mode");
+            ui.selectable_value(&mut graph_creation_state.mode, Mode::Edge, "Edge mode");
+            if ui.button("Delete").clicked() {
+                match graph_creation_state.selected_item {
+                    SelectedItem::Vertex(i) => {
+                        graph_creation_state.graph.vertices.remove(i);
+                        graph_creation_state.graph.adjacency_list.remove(i);
+
+                        // We will go through the adjaceny list.
+                        // We will remove all occurences of i and relabel any vertex v greater than i as v - 1.
+                        graph_creation_state
+                            .graph
+                            .adjacency_list
+                            .iter_mut()
+                            .for_each(|list| {
+                                let mut removed_vertex_position = None;
+                                for (index, v) in list.iter_mut().enumerate() {
+                                    match (*v).cmp(&i) {
+                                        Ordering::Greater => *v -= 1,
+                                        Ordering::Equal => removed_vertex_position = Some(index),
+                                        Ordering::Less => {}
+                                    }
+                                }
+                                if let Some(index) = removed_vertex_position {
+                                    list.remove(index);
+                                }
+                            });
+                        graph_creation_state.selected_item = SelectedItem::None;
+                    }
+                    SelectedItem::Edge(i, j) => {
+                        let adjaceny_list_i = &mut graph_creation_state.graph.adjacency_list[i];
+                        for k in 0..adjaceny_list_i.len() {
+                            if adjaceny_list_i[k] == j {
+                                adjaceny_list_i.remove(k);
+                                break;
+                            }
+                        }
+
+                        let adjaceny_list_j = &mut graph_creation_state.graph.adjacency_list[j];
+                        for k in 0..adjaceny_list_j.len() {
+                            if adjaceny_list_j[k] == i {
+                                adjaceny_list_j.remove(k);
+                                break;
+                            }
+                        }
+
+                        graph_creation_state.selected_item = SelectedItem::None;
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // Laying out the graph keeps the indices of the vertices, so the selection stays valid.
+        ui.horizontal(|ui| {
+            ui.label("Layout");
+            for layout in Layout::ALL {
+                if ui.button(layout.name()).clicked() {
+                    layout.apply(&mut graph_creation_state.graph);
+                }
+            }
+        });
+
+        Frame::canvas(ui.style()).show(ui, |ui| {
+            show_graph_editor(ui, graph_creation_state);
+        });
+
+        ui.horizontal(|ui| {
+            let edited_graph = graph_creation_state.edited_graph;
+            let label = if edited_graph.is_some() {
+                "Save"
+            } else {
+                "Create"
+            };
+            if ui.button(label).clicked() {
+                if graph_creation_state.graph.name.is_empty()
+                    || graph_creation_state.graph.vertices.is_empty()
+                {
+                    return;
+                }
+                match edited_graph {
+                    Some(index) => {
+                        graphs[index] = graph_creation_state.graph.clone();
+                        view = Some(View::GraphManager(GraphManagerState::new(graphs, index)));
+                    }
+                    None => {
+                        graphs.push(graph_creation_state.graph.clone());
+                        *current_graph = graphs.len() - 1;
+                        view = Some(View::GameSettingsSelection);
+                    }
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                view = Some(match edited_graph {
+                    Some(index) => View::GraphManager(GraphManagerState::new(graphs, index)),
+                    None => View::GameSettingsSelection,
+                });
+            }
+        });
+    });
+
+    view
+}
+
+// The first of "<name> copy", "<name> copy 2", ... which no graph has.
+fn copy_name(graphs: &[Graph], name: &str) -> String {
+    (1..)
+        .map(|copy| match copy {
+            1 => format!("{name} copy"),
+            _ => format!("{name} copy {copy}"),
+        })
+        .find(|copy_name| graphs.iter().all(|graph| &graph.name != copy_name))
+        .unwrap()
+}
+
+// Removes a graph, keeping the current graph selected (or the next one if it's the removed
+// graph), so current_graph stays in bounds. The last graph can't be removed, as we always
+// need a graph to play on.
+fn delete_graph(graphs: &mut Vec<Graph>, current_graph: &mut usize, index: usize) {
+    if graphs.len() <= 1 || index >= graphs.len() {
+        return;
+    }
+    graphs.remove(index);
+    if *current_graph > index {
+        *current_graph -= 1;
+    }
+    *current_graph = (*current_graph).min(graphs.len() - 1);
+}
+
+fn graph_manager(
+    ctx: &egui::Context,
+    graph_manager_state: &mut GraphManagerState,
+    graphs: &mut Vec<Graph>,
+    current_graph: &mut usize,
+) -> Option<View> {
+    let mut view = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Cops and Robbers");
+
+        ui.add_space(5.0);
+
+        let GraphManagerState {
+            selected_graph,
+            name,
+            confirm_delete,
+        } = graph_manager_state;
+
+        ui.horizontal(|ui| {
+            ui.label("Graph");
+            egui::ComboBox::from_id_source("Managed graph")
+                .selected_text(graphs[*selected_graph].name.clone())
+                .show_ui(ui, |ui| {
+                    for (i, graph) in graphs.iter().enumerate() {
+                        if ui
+                            .selectable_value(selected_graph, i, graph.name.clone())
+                            .changed()
+                        {
+                            *name = graph.name.clone();
+                            *confirm_delete = false;
+                        }
+                    }
+                });
+        });
+
+        let graph = &graphs[*selected_graph];
+        let number_of_edges = graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+        ui.label(format!(
+            "{} vertices, {} edges",
+            graph.vertices.len(),
+            number_of_edges
+        ));
+        show_graph(ui, graph);
+
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.add(egui::TextEdit::singleline(name).desired_width(200.0));
+            if ui
+                .add_enabled(
+                    !name.is_empty() && *name != graphs[*selected_graph].name,
+                    egui::Button::new("Rename"),
+                )
+                .clicked()
+            {
+                graphs[*selected_graph].name = name.clone();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Edit").clicked() {
+                view = Some(View::GraphCreation(GraphCreationState {
+                    graph: graphs[*selected_graph].clone(),
+                    edited_graph: Some(*selected_graph),
+                    ..Default::default()
+                }));
+            }
+            if ui.button("Duplicate").clicked() {
+                let mut copy = graphs[*selected_graph].clone();
+                copy.name = copy_name(graphs, &copy.name);
+                graphs.push(copy);
+                *selected_graph = graphs.len() - 1;
+                *name = graphs[*selected_graph].name.clone();
+                *confirm_delete = false;
+            }
+            if ui
+                .add_enabled(graphs.len() > 1, egui::Button::new("Delete"))
+                .on_disabled_hover_text("The last graph can't be deleted.")
+                .clicked()
+            {
+                *confirm_delete = true;
+            }
+        });
+
+        if *confirm_delete {
+            ui.horizontal(|ui| {
+                ui.label(format!("Delete {}?", graphs[*selected_graph].name));
+                if ui.button("Yes").clicked() {
+                    delete_graph(graphs, current_graph, *selected_graph);
+                    *selected_graph = (*selected_graph).min(graphs.len() - 1);
+                    *name = graphs[*selected_graph].name.clone();
+                    *confirm_delete = false;
+                }
+                if ui.button("No").clicked() {
+                    *confirm_delete = false;
+                }
+            });
+        }
+
+        ui.add_space(5.0);
+
+        if ui.button("Back").clicked() {
+            view = Some(View::GameSettingsSelection);
+        }
+    });
+
+    view
+}
+
+// Shows the parameters of a graph family, limited so the graphs stay small enough to play on.
+fn show_graph_generator_parameters(ui: &mut egui::Ui, generator: &mut GraphGenerator) {
+    let parameter = |ui: &mut egui::Ui, label: &str, value: &mut usize, range| {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            ui.add(egui::DragValue::new(value).clamp_range(range));
+        });
+    };
+    match generator {
+        GraphGenerator::Grid { rows, columns } => {
+            parameter(ui, "Rows", rows, 1..=MAX_GRID_SIDE);
+            parameter(ui, "Columns", columns, 1..=MAX_GRID_SIDE);
+        }
+        // Smaller tori would have repeated edges.
+        GraphGenerator::Torus { rows, columns } => {
+            parameter(ui, "Rows", rows, 3..=MAX_GRID_SIDE);
+            parameter(ui, "Columns", columns, 3..=MAX_GRID_SIDE);
+        }
+        GraphGenerator::Cycle { length } => {
+            parameter(ui, "Length", length, 3..=MAX_GENERATED_VERTICES);
+        }
+        GraphGenerator::Complete { size } => {
+            parameter(ui, "Vertices", size, 1..=MAX_COMPLETE_SIZE);
+        }
+        GraphGenerator::CompleteBipartite { left, right } => {
+            parameter(ui, "Left vertices", left, 1..=MAX_COMPLETE_SIZE);
+            parameter(ui, "Right vertices", right, 1..=MAX_COMPLETE_SIZE);
+        }
+        GraphGenerator::Hypercube { dimension } => {
+            parameter(ui, "Dimension", dimension, 1..=MAX_HYPERCUBE_DIMENSION);
+        }
+        GraphGenerator::Petersen | GraphGenerator::Dodecahedron => {}
+        GraphGenerator::RandomTree { size, seed } => {
+            parameter(ui, "Vertices", size, 1..=MAX_GENERATED_VERTICES);
+            seed_parameter(ui, seed);
+        }
+        GraphGenerator::ErdosRenyi {
+            vertices,
+            probability,
+            seed,
+        } => {
+            parameter(ui, "Vertices", vertices, 1..=MAX_GENERATED_VERTICES);
+            ui.add(egui::Slider::new(probability, 0.0..=1.0).text("Edge probability"));
+            seed_parameter(ui, seed);
+        }
+        GraphGenerator::RandomRegular {
+            vertices,
+            degree,
+            seed,
+        } => {
+            parameter(ui, "Vertices", vertices, 1..=MAX_GENERATED_VERTICES);
+            parameter(ui, "Degree", degree, 0..=*vertices - 1);
+            seed_parameter(ui, seed);
+        }
+        GraphGenerator::BarabasiAlbert {
+            vertices,
+            edges_per_vertex,
+            seed,
+        } => {
+            parameter(ui, "Vertices", vertices, 2..=MAX_GENERATED_VERTICES);
+            parameter(
+                ui,
+                "Edges of every new vertex",
+                edges_per_vertex,
+                1..=*vertices - 1,
+            );
+            seed_parameter(ui, seed);
+        }
+        GraphGenerator::RandomGeometric {
+            vertices,
+            radius,
+            seed,
+        } => {
+            parameter(ui, "Vertices", vertices, 1..=MAX_GENERATED_VERTICES);
+            ui.add(egui::Slider::new(radius, 0.0..=1.5).text("Radius"));
+            seed_parameter(ui, seed);
+        }
+    }
+}
+
+// A text field for a seed. We don't use a DragValue, as its value is an f64, which can't hold
+// every u64. While the field is edited we keep its text, so it can be cleared before typing
+// a new seed, and the seed only changes when the text is a number.
+fn seed_edit(ui: &mut egui::Ui, seed: &mut u64) {
+    let id = ui.next_auto_id();
+    let mut text = ui
+        .data_mut(|data| data.get_temp::<String>(id))
+        .unwrap_or_else(|| seed.to_string());
+    let response = ui.add(egui::TextEdit::singleline(&mut text).desired_width(160.0));
+    if let Ok(new_seed) = text.trim().parse() {
+        *seed = new_seed;
+    }
+    if response.has_focus() {
+        ui.data_mut(|data| data.insert_temp(id, text));
+    } else {
+        ui.data_mut(|data| data.remove::<String>(id));
+    }
+}
+
+fn seed_parameter(ui: &mut egui::Ui, seed: &mut u64) {
+    ui.horizontal(|ui| {
+        ui.label("Seed");
+        seed_edit(ui, seed);
+        if ui.button("Random seed").clicked() {
+            *seed = rand::random();
+        }
+    });
+}
+
+fn graph_generation(
+    ctx: &egui::Context,
+    graph_generation_state: &mut GraphGenerationState,
+    graphs: &mut Vec<Graph>,
+    current_graph: &mut usize,
+) -> Option<View> {
+    let mut view = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Cops and Robbers");
+
+        ui.add_space(5.0);
+
+        let GraphGenerationState {
+            generator,
+            largest_component,
+            preview,
+        } = graph_generation_state;
+        ui.horizontal(|ui| {
+            ui.label("Family");
+            egui::ComboBox::from_id_source("Graph family")
+                .selected_text(generator.family_name())
+                .show_ui(ui, |ui| {
+                    for family in GraphGenerator::families() {
+                        let is_selected =
+                            std::mem::discriminant(generator) == std::mem::discriminant(&family);
+                        if ui
+                            .selectable_label(is_selected, family.family_name())
+                            .clicked()
+                            && !is_selected
+                        {
+                            *generator = family;
+                        }
+                    }
+                });
+        });
+        show_graph_generator_parameters(ui, generator);
+        if generator.is_random() {
+            ui.checkbox(
+                largest_component,
+                "Keep only the largest connected component",
+            );
+        }
+
+        ui.add_space(5.0);
+
+        let is_preview_current = matches!(
+            preview,
+            Some((preview_generator, preview_largest_component, _))
+                if preview_generator == generator && preview_largest_component == largest_component
+        );
+        if !is_preview_current {
+            let graph = generator.generate().map(|graph| {
+                if generator.is_random() && *largest_component {
+                    let mut component = largest_connected_component(&graph);
+                    component.name.push_str(" (largest component)");
+                    component
+                } else {
+                    graph
+                }
+            });
+            *preview = Some((*generator, *largest_component, graph));
+        }
+        // We just set the preview, so we can unwrap.
+        let graph = &preview.as_ref().unwrap().2;
+        match graph {
+            Ok(graph) => {
+                let number_of_edges: usize =
+                    graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+                ui.label(format!(
+                    "{}: {} vertices, {} edges",
+                    graph.name,
+                    graph.vertices.len(),
+                    number_of_edges
+                ));
+                show_graph(ui, graph);
+            }
+            Err(error) => {
+                ui.label(error.as_str());
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(graph.is_ok(), egui::Button::new("Add"))
+                .clicked()
+            {
+                if let Ok(graph) = graph {
+                    graphs.push(graph.clone());
+                    *current_graph = graphs.len() - 1;
+                    view = Some(View::GameSettingsSelection);
+                }
+            }
+            if ui.button("Cancel").clicked() {
+                view = Some(View::GameSettingsSelection);
+            }
+        });
+    });
+
+    view
+}
+
+fn graph_file(
+    ctx: &egui::Context,
+    graph_file_state: &mut GraphFileState,
+    graphs: &mut Vec<Graph>,
+    current_graph: &mut usize,
+) -> Option<View> {
+    let mut view = None;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Cops and Robbers");
+
+        ui.add_space(5.0);
+
+        let GraphFileState {
+            path,
+            format,
+            message,
+            pending_file,
+            imported_graph,
+        } = graph_file_state;
+
+        ui.horizontal(|ui| {
+            ui.label("File");
+            if ui
+                .add(egui::TextEdit::singleline(path).desired_width(200.0))
+                .changed()
+            {
+                if let Some(path_format) = GraphFormat::from_path(path) {
+                    *format = path_format;
+                }
+            }
+            egui::ComboBox::from_id_source("Graph format")
+                .selected_text(format.name())
+                .show_ui(ui, |ui| {
+                    for graph_format in GraphFormat::ALL {
+                        if ui
+                            .selectable_value(format, graph_format, graph_format.name())
+                            .clicked()
+                        {
+                            // We change the extension of the path to the format's.
+                            let stem = path
+                                .rsplit_once('.')
+                                .map_or(path.as_str(), |(stem, _)| stem);
+                            *path = format!("{stem}.{}", graph_format.extension());
+                        }
+                    }
+                });
+        });
+        ui.label("Formats without positions, and files without them, are laid out automatically.");
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(format!("Export {}", graphs[*current_graph].name))
+                .clicked()
+            {
+                let result = export_graph(&graphs[*current_graph], *format)
+                    .and_then(|contents| save_file(path, &contents));
+                *message = Some(match result {
+                    Ok(()) => format!("Exported {}.", graphs[*current_graph].name),
+                    Err(error) => error,
+                });
+            }
+            if ui.button("Import").clicked() {
+                *pending_file = Some(load_file(path, ui.ctx()));
+            }
+        });
+
+        if let Some(contents) = pending_file
+            .as_ref()
+            .and_then(|pending_file| pending_file.take())
+        {
+            *pending_file = None;
+            // The file name without its directories and extension names graphs whose file
+            // doesn't name them.
+            let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+            let name = file_name.split('.').next().unwrap_or(file_name);
+            match contents.and_then(|contents| import_graph(&contents, *format, name)) {
+                Ok(graph) => {
+                    *message = None;
+                    *imported_graph = Some(graph);
+                }
+                Err(error) => *message = Some(error),
+            }
+        }
+
+        if let Some(message) = message {
+            ui.label(message.as_str());
+        }
+
+        if let Some(graph) = imported_graph {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Graph name");
+                ui.add(egui::TextEdit::singleline(&mut graph.name).desired_width(200.0));
+            });
+            let number_of_edges: usize =
+                graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2;
+            ui.label(format!(
+                "{} vertices, {} edges",
+                graph.vertices.len(),
+                number_of_edges
+            ));
+            show_graph(ui, graph);
+        }
+
+        ui.horizontal(|ui| {
+            if let Some(graph) = imported_graph {
+                if ui
+                    .add_enabled(!graph.name.is_empty(), egui::Button::new("Add"))
+                    .clicked()
+                {
+                    graphs.push(graph.clone());
+                    *current_graph = graphs.len() - 1;
+                    view = Some(View::GameSettingsSelection);
+                }
+            }
+            if ui.button("Back").clicked() {
+                view = Some(View::GameSettingsSelection);
+            }
+        });
+    });
+
+    view
+}
+
+// Shows the controls to export and import a MENACE brain.
+// Returns the imported brain once its file has been loaded.
+fn show_brain_file(
+    ui: &mut egui::Ui,
+    brain_file: &mut BrainFileState,
+    brain: impl FnOnce() -> MenaceBrain,
+) -> Option<MenaceBrain> {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Brain:").strong());
+        ui.add(egui::TextEdit::singleline(&mut brain_file.path).desired_width(120.0));
+        if ui.button("Export").clicked() {
+            let result = serde_json::to_string(&brain())
+                .map_err(|error| error.to_string())
+                .and_then(|contents| save_file(&brain_file.path, &contents));
+            brain_file.message = Some(match result {
+                Ok(()) => "Exported the brain.".to_string(),
+                Err(error) => error,
+            });
+        }
+        if ui.button("Import").clicked() {
+            brain_file.pending_file = Some(load_file(&brain_file.path, ui.ctx()));
+        }
+    });
+
+    let mut imported_brain = None;
+    if let Some(contents) = brain_file
+        .pending_file
+        .as_ref()
+        .and_then(|pending_file| pending_file.take())
+    {
+        brain_file.pending_file = None;
+        match contents.and_then(|contents| {
+            serde_json::from_str(&contents)
+                .map_err(|error| format!("Couldn't read the brain: {error}"))
+        }) {
+            Ok(brain) => imported_brain = Some(brain),
+            Err(error) => brain_file.message = Some(error),
+        }
+    }
+
+    if let Some(message) = &brain_file.message {
+        ui.label(message.as_str());
+    }
+
+    imported_brain
+}
+
+// Shows a recorded match of the game turn by turn, with controls to play it (at the speed of
+// the game), step through it and scrub to any turn, and to export it. The number of recorded
+// matches can be changed.
+fn show_replay<'a>(
+    ui: &mut egui::Ui,
+    replay_viewing_state: &mut ReplayViewingState,
+    game: &'a mut Game,
+    speed: f32,
+    replay: impl FnOnce(&'a Game, &'a MatchRecord) -> Replay<'a>,
+) {
+    let ReplayViewingState {
+        match_number,
+        turn,
+        playing,
+        last_step_time,
+        path,
+        message,
+    } = replay_viewing_state;
+
+    ui.label(RichText::new("Replay").strong());
+    ui.horizontal(|ui| {
+        ui.label("Recorded matches");
+        let mut max_recorded_matches = game.max_recorded_matches;
+        let response = ui
+            .add(
+                egui::DragValue::new(&mut max_recorded_matches)
+                    .clamp_range(1..=MAX_RECORDED_MATCHES),
+            )
+            .on_hover_text("Only the last matches are kept, the older ones are dropped");
+        if response.changed() {
+            game.set_max_recorded_matches(max_recorded_matches);
+        }
+    });
+    let game: &'a Game = game;
+
+    let Some(last_record) = game.records.back() else {
+        ui.label("No match has been played yet.");
+        return;
+    };
+
+    // Only the last matches are kept, so the shown match may have been dropped.
+    let record = match_number
+        .and_then(|match_number| {
+            game.records
+                .iter()
+                .find(|record| record.match_number == match_number)
+        })
+        .unwrap_or(last_record);
+    if *match_number != Some(record.match_number) {
+        *match_number = Some(record.match_number);
+        *turn = 0;
+        *playing = false;
+    }
+
+    ui.label(format!("Seed: {}", game.seed));
+    egui::ComboBox::from_label("Match")
+        .selected_text(format!("Match {}", record.match_number))
+        .show_ui(ui, |ui| {
+            for record in game.records.iter().rev() {
+                let result = if record.cop_won {
+                    "cops won"
+                } else {
+                    "robber escaped"
+                };
+                if ui
+                    .selectable_label(
+                        *match_number == Some(record.match_number),
+                        format!("Match {} ({result})", record.match_number),
+                    )
+                    .clicked()
+                {
+                    *match_number = Some(record.match_number);
+                    *turn = 0;
+                    *playing = false;
+                }
+            }
+        });
+
+    // The selection may have changed, so we look the match up again.
+    let record = game
+        .records
+        .iter()
+        .find(|record| Some(record.match_number) == *match_number)
+        .unwrap_or(record);
+    let last_turn = record.turns.len() - 1;
+    *turn = (*turn).min(last_turn);
+
+    let time = ui.input(|input| input.time);
+    if *playing {
+        let step_time = (2.0 * ANIMATION_TIME / speed) as f64;
+        if time - *last_step_time >= step_time {
+            *last_step_time = time;
+            *turn += 1;
+        }
+        if *turn >= last_turn {
+            *turn = last_turn;
+            *playing = false;
+        } else {
+            ui.ctx()
+                .request_repaint_after(Duration::from_secs_f64(step_time));
+        }
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(*turn > 0, egui::Button::new("Step back"))
+            .clicked()
+        {
+            *turn -= 1;
+            *playing = false;
+        }
+        if *playing {
+            if ui.button("Pause").clicked() {
+                *playing = false;
+            }
+        } else if ui.button("Play").clicked() {
+            // Playing from the end starts the match again.
+            if *turn == last_turn {
+                *turn = 0;
+            }
+            *playing = true;
+            *last_step_time = time;
+        }
+        if ui
+            .add_enabled(*turn < last_turn, egui::Button::new("Step forward"))
+            .clicked()
+        {
+            *turn += 1;
+            *playing = false;
+        }
+    });
+    ui.add(egui::Slider::new(turn, 0..=last_turn).text("Turn"));
+
+    let description = match *turn {
+        0 => "The cops start.",
+        1 => "The robber starts.",
+        turn if turn % 2 == 0 => "The cops move.",
+        _ => "The robber moves.",
+    };
+    ui.label(description);
+    if *turn == last_turn {
+        ui.label(
+            RichText::new(if record.cop_won {
+                "The cops won."
+            } else {
+                "The robber escaped."
+            })
+            .strong(),
+        );
+    }
+
+    let recorded_turn = &record.turns[*turn];
+    show_graph_with_cops_and_robber(
+        ui,
+        Some(&recorded_turn.cop_positions),
+        recorded_turn.robber_position,
+        &game.graph,
+        330.0,
+    );
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Replay:").strong());
+        ui.add(egui::TextEdit::singleline(path).desired_width(120.0));
+        if ui.button("Export").clicked() {
+            let result = serde_json::to_string(&replay(game, record))
+                .map_err(|error| error.to_string())
+                .and_then(|contents| save_file(path, &contents));
+            *message = Some(match result {
+                Ok(()) => "Exported the replay.".to_string(),
+                Err(error) => error,
+            });
+        }
+    });
+    if let Some(message) = message {
+        ui.label(message.as_str());
+    }
+}
+
+// The results of the matches as CSV. The cop start positions are separated by spaces.
+fn match_results_csv(match_results: &[MatchResult]) -> String {
+    let mut csv =
+        "match,winner,capture_turn,cop_start_positions,robber_start_position\n".to_string();
+    for result in match_results {
+        let winner = if result.cop_won { "cop" } else { "robber" };
+        let capture_turn = result
+            .capture_turn
+            .map_or(String::new(), |capture_turn| capture_turn.to_string());
+        let cop_start_positions: Vec<_> = result
+            .cop_start_positions
+            .iter()
+            .map(|position| position.to_string())
+            .collect();
+        csv.push_str(&format!(
+            "{},{winner},{capture_turn},{},{}\n",
+            result.match_number,
+            cop_start_positions.join(" "),
+            result.robber_start_position
+        ));
+    }
+    csv
+}
+
+// The points of the cop wins graph as CSV.
+fn cop_scores_csv(cop_scores: &[CopScore]) -> String {
+    let mut csv = "matches,cop_wins,cop_win_fraction\n".to_string();
+    for cop_score in cop_scores {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            cop_score.matches,
+            cop_score.cop_wins,
+            cop_score.cop_wins as f64 / cop_score.matches as f64
+        ));
+    }
+    csv
+}
+
+// Shows the controls to export the statistics of the game. The CSV export writes the match
+// results and the cop scores to two files named after the path, the JSON export one file.
+fn show_statistics_file<'a>(
+    ui: &mut egui::Ui,
+    path: &mut String,
+    message: &mut Option<String>,
+    statistics: impl FnOnce() -> GameStatistics<'a>,
+) {
+    let (export_csv, export_json) = ui
+        .horizontal(|ui| {
+            ui.label(RichText::new("Statistics:").strong());
+            ui.add(egui::TextEdit::singleline(path).desired_width(120.0));
+            (
+                ui.button("Export CSV").clicked(),
+                ui.button("Export JSON").clicked(),
+            )
+        })
+        .inner;
+
+    if export_csv {
+        let statistics = statistics();
+        let matches_path = format!("{path}_matches.csv");
+        let cop_scores_path = format!("{path}_cop_scores.csv");
+        let result = save_file(&matches_path, &match_results_csv(statistics.matches))
+            .and_then(|()| save_file(&cop_scores_path, &cop_scores_csv(&statistics.cop_scores)));
+        *message = Some(match result {
+            Ok(()) => format!("Exported {matches_path} and {cop_scores_path}."),
+            Err(error) => error,
+        });
+    } else if export_json {
+        let json_path = format!("{path}.json");
+        let result = serde_json::to_string(&statistics())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| save_file(&json_path, &contents));
+        *message = Some(match result {
+            Ok(()) => format!("Exported {json_path}."),
+            Err(error) => error,
+        });
+    }
+
+    if let Some(message) = message {
+        ui.label(message.as_str());
+    }
+}
+
+// Lets the user choose which cop bag (or Q-table) to show: the start bag, or the bag of a position
+// chosen by clicking the vertices of the players.
+fn show_cop_bag_selection(
+    ui: &mut egui::Ui,
+    bag_key: &mut Option<(Vec<usize>, usize)>,
+    editing_vertex: &mut BagEditingVertex,
+    graph: &Graph,
+    number_of_cops: u8,
+) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Bag:").strong());
+
+        // start_bag is a temporary bool we can change,
+        // we use it to change whether we're currently viewing
+        // the start bag or a non start bag.
+        let mut start_bag = bag_key.is_none();
+        let selected_text = if start_bag { "Start" } else { "Non start" };
+        egui::ComboBox::from_id_source("Cop bag type")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut start_bag, true, "Start");
+                ui.selectable_value(&mut start_bag, false, "Non start");
+            });
+        // if start_bag changes, we update the bag key
+        if start_bag {
+            if bag_key.is_some() {
+                *bag_key = None;
+            }
+        } else if bag_key.is_none() {
+            *bag_key = Some((vec![0; number_of_cops as usize], 0));
+        }
+    });
+
+    // If we are viewing a non-start bag, we allow the user to select the bag.
+    if let Some((cops, robber)) = bag_key {
+        ui.horizontal(|ui| {
+            ui.selectable_value(editing_vertex, BagEditingVertex::None, "View");
+            for i in 0..cops.len() {
+                ui.selectable_value(
+                    editing_vertex,
+                    BagEditingVertex::Cop(i),
+                    format!("Edit cop {i}"),
+                );
+            }
+            ui.selectable_value(editing_vertex, BagEditingVertex::Robber, "Edit robber");
+        });
+
+        match editing_vertex {
+            BagEditingVertex::None => {
+                show_graph_with_cops_and_robber(ui, Some(cops), Some(*robber), graph, 300.0);
+            }
+            BagEditingVertex::Cop(i) => {
+                select_graph_vertex(ui, &mut cops[*i], graph, true);
+            }
+            BagEditingVertex::Robber => {
+                select_graph_vertex(ui, robber, graph, false);
+            }
+        }
+    }
+}
+
+// Lets the user choose which robber bag (or Q-table) to show, see show_cop_bag_selection.
+fn show_robber_bag_selection(
+    ui: &mut egui::Ui,
+    bag_key: &mut (Vec<usize>, Option<usize>),
+    editing_vertex: &mut BagEditingVertex,
+    graph: &Graph,
+    number_of_cops: u8,
+) {
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Bag:").strong());
+
+        // start_bag is a temporary bool we can change,
+        // we use it to change whether we're currently viewing
+        // a start bag or a non start bag.
+        let mut start_bag = bag_key.1.is_none();
+        let selected_text = if start_bag { "Start" } else { "Non start" };
+        egui::ComboBox::from_id_source("Robber bag type")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut start_bag, true, "Start");
+                ui.selectable_value(&mut start_bag, false, "Non start");
+            });
+        // if start_bag changes, we update the bag key
+        if start_bag {
+            if bag_key.1.is_some() {
+                *bag_key = (vec![0; number_of_cops as usize], None);
+                *editing_vertex = BagEditingVertex::None;
+            }
+        } else if bag_key.1.is_none() {
+            *bag_key = (vec![0; number_of_cops as usize], Some(0));
+            *editing_vertex = BagEditingVertex::None;
+        }
+    });
+
+    // We allow the user to select the bag.
+    let cops = &mut bag_key.0;
+    ui.horizontal(|ui| {
+        ui.selectable_value(editing_vertex, BagEditingVertex::None, "View");
+        for i in 0..cops.len() {
+            ui.selectable_value(
+                editing_vertex,
+                BagEditingVertex::Cop(i),
+                format!("Edit cop {i}"),
+            );
+        }
+        if bag_key.1.is_some() {
+            ui.selectable_value(editing_vertex, BagEditingVertex::Robber, "Edit robber");
+        }
+    });
+    match editing_vertex {
+        BagEditingVertex::None => {
+            show_graph_with_cops_and_robber(ui, Some(cops), bag_key.1, graph, 300.0);
+        }
+        BagEditingVertex::Cop(i) => {
+            select_graph_vertex(ui, &mut cops[*i], graph, true);
+        }
+        BagEditingVertex::Robber => {
+            if let Some(robber) = &mut bag_key.1 {
+                select_graph_vertex(ui, robber, graph, false);
+            } else {
+                // Shouldn't be editing robber, so we change editing vertex.
+                *editing_vertex = BagEditingVertex::None;
+            }
+        }
+    }
+}
+
+// Shows the Q-table of a Q-learning cop like the bags of a MENACE cop.
+fn show_qlearning_cop(
+    ui: &mut egui::Ui,
+    cop: &QLearningCop,
+    viewing_state: &mut CopBagViewingState,
+    graph: &Graph,
+    number_of_cops: u8,
+) {
+    let CopBagViewingState {
+        bag_key,
+        editing_vertex,
+        sort_by_counts,
+        ..
+    } = viewing_state;
+
+    ui.label(RichText::new("Q-learning cop").strong());
+    ui.label(format!(
+        "Symmetries: {}",
+        cop.symmetries(graph).number_of_automorphisms()
+    ));
+    ui.label(format!("Q-tables: {}", cop.tables().len()));
+
+    ui.add_space(10.0);
+
+    show_cop_bag_selection(ui, bag_key, editing_vertex, graph, number_of_cops);
+
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Moves:").strong());
+        ui.toggle_value(sort_by_counts, "Sort moves");
+    });
+
+    let position = bag_key.as_ref().map(|(cops, robber)| (cops, *robber));
+    let table = cop.table(graph, position);
+    let moves = if *sort_by_counts {
+        table.best_values(MAX_SHOWN_MOVES)
+    } else {
+        table.values().take(MAX_SHOWN_MOVES).collect()
+    };
+    for (choice, value) in moves {
+        let new_cop_positions = cop.decode_move(graph, position, choice);
+        ui.horizontal(|ui| {
+            show_graph_with_cops_and_robber(
+                ui,
+                Some(&new_cop_positions),
+                position.map(|(_, robber)| robber),
+                graph,
+                180.0,
+            );
+            ui.label(format!("{value:.3}"));
+        });
+    }
+    show_number_of_hidden_moves(ui, table.number_of_moves());
+}
+
+// Shows the Q-table of a Q-learning robber like the bags of a MENACE robber.
+fn show_qlearning_robber(
+    ui: &mut egui::Ui,
+    robber: &QLearningRobber,
+    viewing_state: &mut RobberBagViewingState,
+    graph: &Graph,
+    number_of_cops: u8,
+) {
+    let RobberBagViewingState {
+        bag_key,
+        editing_vertex,
+        sort_by_counts,
+        ..
+    } = viewing_state;
 
-        ui.horizontal(|ui| {
-            ui.selectable_value(&mut graph_creation_state.mode, Mode::Vertex, "Vertex mode");
-            ui.selectable_value(&mut graph_creation_state.mode, Mode::Edge, "Edge mode");
-            if ui.button("Delete").clicked() {
-                match graph_creation_state.selected_item {
-                    SelectedItem::Vertex(i) => {
-                        graph_creation_state.graph.vertices.remove(i);
-                        graph_creation_state.graph.adjacency_list.remove(i);
+    ui.label(RichText::new("Q-learning robber").strong());
+    ui.label(format!(
+        "Symmetries: {}",
+        robber.symmetries(graph).number_of_automorphisms()
+    ));
+    ui.label(format!("Q-tables: {}", robber.tables().len()));
 
-                        // We will go through the adjaceny list.
-                        // We will remove all occurences of i and relabel any vertex v greater than i as v - 1.
-                        graph_creation_state
-                            .graph
-                            .adjacency_list
-                            .iter_mut()
-                            .for_each(|list| {
-                                let mut removed_vertex_position = None;
-                                for (index, v) in list.iter_mut().enumerate() {
-                                    match (*v).cmp(&i) {
-                                        Ordering::Greater => *v -= 1,
-                                        Ordering::Equal => removed_vertex_position = Some(index),
-                                        Ordering::Less => {}
-                                    }
-                                }
-                                if let Some(index) = removed_vertex_position {
-                                    list.remove(index);
-                                }
-                            });
-                        graph_creation_state.selected_item = SelectedItem::None;
-                    }
-                    SelectedItem::Edge(i, j) => {
-                        let adjaceny_list_i = &mut graph_creation_state.graph.adjacency_list[i];
-                        for k in 0..adjaceny_list_i.len() {
-                            if adjaceny_list_i[k] == j {
-                                adjaceny_list_i.remove(k);
-                                break;
-                            }
-                        }
+    ui.add_space(10.0);
 
-                        let adjaceny_list_j = &mut graph_creation_state.graph.adjacency_list[j];
-                        for k in 0..adjaceny_list_j.len() {
-                            if adjaceny_list_j[k] == i {
-                                adjaceny_list_j.remove(k);
-                                break;
-                            }
-                        }
+    show_robber_bag_selection(ui, bag_key, editing_vertex, graph, number_of_cops);
 
-                        graph_creation_state.selected_item = SelectedItem::None;
-                    }
-                    _ => {}
-                }
-            }
-        });
+    ui.add_space(10.0);
 
-        Frame::canvas(ui.style()).show(ui, |ui| {
-            show_graph_editor(ui, graph_creation_state);
-        });
+    ui.horizontal(|ui| {
+        ui.label(RichText::new("Moves:").strong());
+        ui.toggle_value(sort_by_counts, "Sort moves");
+    });
 
+    let table = robber.table(graph, &bag_key.0, bag_key.1);
+    let moves = if *sort_by_counts {
+        table.best_values(MAX_SHOWN_MOVES)
+    } else {
+        table.values().take(MAX_SHOWN_MOVES).collect()
+    };
+    for (choice, value) in moves {
+        let new_robber_position = robber.decode_move(graph, &bag_key.0, bag_key.1, choice);
         ui.horizontal(|ui| {
-            if ui.button("Create").clicked() {
-                if graph_creation_state.graph.name.is_empty()
-                    || graph_creation_state.graph.vertices.is_empty()
-                {
-                    return;
-                }
-                graphs.push(graph_creation_state.graph.clone());
-                *current_graph = graphs.len() - 1;
-                view = Some(View::GameSettingsSelection);
-            }
-            if ui.button("Cancel").clicked() {
-                view = Some(View::GameSettingsSelection);
-            }
+            show_graph_with_cops_and_robber(
+                ui,
+                Some(&bag_key.0),
+                Some(new_robber_position),
+                graph,
+                180.0,
+            );
+            ui.label(format!("{value:.3}"));
         });
-    });
+    }
+    show_number_of_hidden_moves(ui, table.number_of_moves());
+}
 
-    view
+fn show_number_of_hidden_moves(ui: &mut egui::Ui, number_of_moves: u64) {
+    if number_of_moves > MAX_SHOWN_MOVES as u64 {
+        ui.label(format!(
+            "Showing {MAX_SHOWN_MOVES} of {number_of_moves} moves."
+        ));
+    }
+}
+
+fn show_solution(ui: &mut egui::Ui, solution: Option<&Solution>, number_of_steps: u8) {
+    let Some(solution) = solution else {
+        ui.label("Solving the game...");
+        return;
+    };
+    match solution.capture_time() {
+        Some(capture_time) if capture_time <= number_of_steps as u32 => {
+            ui.label(format!(
+                "With optimal play the cops win, capturing the robber within {capture_time} steps."
+            ));
+        }
+        Some(capture_time) => {
+            ui.label(format!(
+                "With optimal play the robber wins. The cops need {capture_time} steps to capture the robber."
+            ));
+        }
+        None => {
+            ui.label("With optimal play the robber wins, escaping the cops forever.");
+        }
+    }
 }
 
 fn game_details(
     ctx: &egui::Context,
     game_and_animation_state: &mut Arc<Mutex<Option<GameViewState>>>,
-    number_of_immediate_games: &mut Arc<Mutex<Option<u32>>>,
     number_of_cops: u8,
 ) {
     egui::SidePanel::right("Details")
@@ -926,25 +3002,24 @@ fn game_details(
         .resizable(false)
         .show(ctx, |ui| {
             egui::ScrollArea::both().auto_shrink([false, true]).show(ui, |ui| {
-                let number_of_immediate_games = number_of_immediate_games.lock();
-
-                // Check if their are games to be computed
-                // - if so, we shouldn't get the game_and_animation_state lock right now,
-                // as the computation thread may be locking it.
-                if number_of_immediate_games.is_some() {
-                    ui.spinner();
-                    return;
-                }
-
                 let mut game_and_animation_state = game_and_animation_state.lock();
                 if let Some(game_and_animation_state) = &mut (*game_and_animation_state)
                 {
                     let GameViewState {
                         game,
+                        cop_name,
+                        robber_name,
                         game_statistics_view,
-                        menace_cop_viewing_state,
-                        menace_robber_viewing_state,
+                        cop_bag_viewing_state,
+                        robber_bag_viewing_state,
                         cop_scores,
+                        match_results,
+                        statistics_path,
+                        statistics_message,
+                        cop_settings,
+                        robber_settings,
+                        replay_viewing_state,
+                        speed,
                         ..
                     } = game_and_animation_state;
 
@@ -952,104 +3027,47 @@ fn game_details(
                         ui.selectable_value(game_statistics_view, GameStatisticsView::Cop, "Cop");
                         ui.selectable_value(game_statistics_view, GameStatisticsView::Robber, "Robber");
                         ui.selectable_value(game_statistics_view, GameStatisticsView::Graph, "Graph");
+                        ui.selectable_value(game_statistics_view, GameStatisticsView::Replay, "Replay");
                     });
 
                     match game_statistics_view {
                         GameStatisticsView::Cop => {
-                            match &mut game.cop {
-                                Cop::Random(_) => {
-                                    ui.label(RichText::new("Random cop").strong());
+                            match game.cop.as_any_mut().downcast_mut::<MenaceCop>() {
+                                None => {
+                                    if let Some(cop) = game.cop.as_any().downcast_ref::<QLearningCop>() {
+                                        show_qlearning_cop(ui, cop, cop_bag_viewing_state.as_mut().unwrap(), &game.graph, number_of_cops);
+                                    } else {
+                                        ui.label(RichText::new(format!("{cop_name} cop")).strong());
+                                        if let Some(cop) = game.cop.as_any().downcast_ref::<OptimalCop>() {
+                                            show_solution(ui, cop.solution(), game.number_of_steps);
+                                        }
+                                    }
                                 }
-                                Cop::Menace(cop) => {
-                                    let MenaceCopViewingState {
+                                Some(cop) => {
+                                    let CopBagViewingState {
                                         bag_key,
                                         editing_vertex,
                                         sort_by_counts,
-                                    } = menace_cop_viewing_state.as_mut().unwrap();
+                                        brain_file,
+                                    } = cop_bag_viewing_state.as_mut().unwrap();
 
                                     ui.label(RichText::new("Menace cop").strong());
+                                    ui.label(format!(
+                                        "Symmetries: {}",
+                                        cop.symmetries(&game.graph).number_of_automorphisms()
+                                    ));
+                                    ui.label(format!("Bags: {}", cop.bags.len()));
+
+                                    if let Some(brain) = show_brain_file(ui, brain_file, || cop.brain(&game.graph)) {
+                                        brain_file.message = Some(match cop.load_brain(&game.graph, brain) {
+                                            Ok(()) => "Imported the brain.".to_string(),
+                                            Err(error) => error,
+                                        });
+                                    }
 
                                     ui.add_space(10.0);
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Bag:").strong());
-
-                                        // start_bag is a temporary bool we can change,
-                                        // we use it to change whether we're currently viewing
-                                        // the start bag or a non start bag.
-                                        let mut start_bag = bag_key.is_none();
-                                        let selected_text =
-                                            if start_bag { "Start" } else { "Non start" };
-                                        egui::ComboBox::from_id_source("Cop bag type")
-                                            .selected_text(selected_text)
-                                            .show_ui(ui, |ui| {
-                                                ui.selectable_value(&mut start_bag, true, "Start");
-                                                ui.selectable_value(
-                                                    &mut start_bag,
-                                                    false,
-                                                    "Non start",
-                                                );
-                                            });
-                                        // if start_bag changes, we update menace_cop_viewing_bag
-                                        if start_bag {
-                                            if bag_key.is_some() {
-                                                *bag_key = None;
-                                            }
-                                        } else if bag_key.is_none() {
-                                            *bag_key = Some((vec![0; number_of_cops as usize], 0));
-                                        }
-                                    });
-
-                                    // If we are viewing a non-start bag, we allow the user to select the bag.
-                                    if let Some((cops, robber)) = bag_key {
-                                        ui.horizontal(|ui| {
-                                            ui.selectable_value(
-                                                editing_vertex,
-                                                MenaceEditingVertex::None,
-                                                "View",
-                                            );
-                                            for i in 0..cops.len() {
-                                                ui.selectable_value(
-                                                    editing_vertex,
-                                                    MenaceEditingVertex::Cop(i),
-                                                    format!("Edit cop {i}"),
-                                                );
-                                            }
-                                            ui.selectable_value(
-                                                editing_vertex,
-                                                MenaceEditingVertex::Robber,
-                                                "Edit robber",
-                                            );
-                                        });
-
-                                        match editing_vertex {
-                                            MenaceEditingVertex::None => {
-                                                show_graph_with_cops_and_robber(
-                                                    ui,
-                                                    Some(cops),
-                                                    Some(*robber),
-                                                    &game.graph,
-                                                    300.0,
-                                                );
-                                            }
-                                            MenaceEditingVertex::Cop(i) => {
-                                                select_graph_vertex(
-                                                    ui,
-                                                    &mut cops[*i],
-                                                    &game.graph,
-                                                    true,
-                                                );
-                                            }
-                                            MenaceEditingVertex::Robber => {
-                                                select_graph_vertex(
-                                                    ui,
-                                                    robber,
-                                                    &game.graph,
-                                                    false,
-                                                );
-                                            }
-                                        }
-                                    }
+                                    show_cop_bag_selection(ui, bag_key, editing_vertex, &game.graph, number_of_cops);
 
                                     ui.add_space(10.0);
 
@@ -1058,448 +3076,97 @@ fn game_details(
                                         ui.toggle_value(sort_by_counts, "Sort moves");
                                     });
 
-                                    let bag = cop.bags.get(bag_key);
-                                    if let Some((cops, robber)) = bag_key {
-                                        // Non start position
-                                        match bag {
-                                            None => {
-                                                let mut choices = 1;
-                                                for cop in cops.iter() {
-                                                    choices *= game.graph.adjacency_list[*cop]
-                                                        .len()
-                                                        + 1;
-                                                }
-                                                for mut choice in 0..choices {
-                                                    let mut position = vec![];
-                                                    for &cop in cops.iter() {
-                                                        let neighbours =
-                                                            &game.graph.adjacency_list[cop];
-                                                        let new_cop_position =
-                                                            choice % (neighbours.len() + 1);
-                                                        if new_cop_position == neighbours.len()
-                                                        {
-                                                            position.push(cop);
-                                                        } else {
-                                                            position.push(
-                                                                neighbours[new_cop_position],
-                                                            );
-                                                        }
-                                                        choice /= neighbours.len() + 1;
-                                                    }
-                                                    ui.horizontal(|ui| {
-                                                        show_graph_with_cops_and_robber(
-                                                            ui,
-                                                            Some(&position),
-                                                            Some(*robber),
-                                                            &game.graph,
-                                                            180.0,
-                                                        );
-                                                        ui.label("50");
-                                                    });
-                                                }
-                                            }
-                                            Some(bag) => {
-                                                let positions_and_counts = bag
-                                                    .counts
-                                                    .iter()
-                                                    .enumerate()
-                                                    .map(|(mut choice, count)| {
-                                                        let mut position = vec![];
-                                                        for &cop in cops.iter() {
-                                                            let neighbours =
-                                                                &game.graph.adjacency_list[cop];
-                                                            let new_cop_position =
-                                                                choice % (neighbours.len() + 1);
-                                                            if new_cop_position
-                                                                == neighbours.len()
-                                                            {
-                                                                position.push(cop);
-                                                            } else {
-                                                                position.push(
-                                                                    neighbours
-                                                                        [new_cop_position],
-                                                                );
-                                                            }
-                                                            choice /= neighbours.len() + 1;
-                                                        }
-                                                        (position, count)
-                                                    });
-                                                if *sort_by_counts {
-                                                    let mut positions_and_counts =
-                                                        positions_and_counts
-                                                            .collect::<Vec<_>>();
-                                                    positions_and_counts
-                                                        .sort_by_key(|(_, count)| **count);
-                                                    for (position, count) in
-                                                        positions_and_counts.iter().rev()
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(position),
-                                                                Some(*robber),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                } else {
-                                                    for (position, count) in
-                                                        positions_and_counts
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(&position),
-                                                                Some(*robber),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
+                                    let position = bag_key.as_ref().map(|(cops, robber)| (cops, *robber));
+                                    let bag = cop.bag(&game.graph, position);
+                                    let moves = if *sort_by_counts {
+                                        bag.most_tokens(MAX_SHOWN_MOVES)
                                     } else {
-                                        match bag {
-                                            None => {
-                                                let choices = game
-                                                    .graph
-                                                    .vertices
-                                                    .len()
-                                                    .pow(number_of_cops as u32);
-                                                for mut choice in 0..choices {
-                                                    let mut position = vec![];
-                                                    for _ in 0..number_of_cops {
-                                                        position.push(
-                                                            choice % game.graph.vertices.len(),
-                                                        );
-                                                        choice /= game.graph.vertices.len();
-                                                    }
-                                                    ui.horizontal(|ui| {
-                                                        show_graph_with_cops_and_robber(
-                                                            ui,
-                                                            Some(&position),
-                                                            None,
-                                                            &game.graph,
-                                                            180.0,
-                                                        );
-                                                        ui.label("50");
-                                                    });
-                                                }
-                                            }
-                                            Some(bag) => {
-                                                let positions_and_counts = bag
-                                                    .counts
-                                                    .iter()
-                                                    .enumerate()
-                                                    .map(|(mut choice, count)| {
-                                                        let mut position = vec![];
-                                                        for _ in 0..number_of_cops {
-                                                            position.push(
-                                                                choice
-                                                                    % game.graph.vertices.len(),
-                                                            );
-                                                            choice /= game.graph.vertices.len();
-                                                        }
-                                                        (position, count)
-                                                    });
-                                                if *sort_by_counts {
-                                                    let mut positions_and_counts =
-                                                        positions_and_counts
-                                                            .collect::<Vec<_>>();
-                                                    positions_and_counts
-                                                        .sort_by_key(|(_, count)| **count);
-                                                    for (position, count) in
-                                                        positions_and_counts.iter().rev()
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(position),
-                                                                None,
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                } else {
-                                                    for (position, count) in
-                                                        positions_and_counts
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(&position),
-                                                                None,
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
+                                        bag.counts().take(MAX_SHOWN_MOVES).collect()
+                                    };
+                                    for (choice, count) in moves {
+                                        let new_cop_positions = cop.decode_move(&game.graph, position, choice);
+                                        ui.horizontal(|ui| {
+                                            show_graph_with_cops_and_robber(
+                                                ui,
+                                                Some(&new_cop_positions),
+                                                position.map(|(_, robber)| robber),
+                                                &game.graph,
+                                                180.0,
+                                            );
+                                            ui.label(count.to_string());
+                                        });
                                     }
+                                    show_number_of_hidden_moves(ui, bag.number_of_moves());
                                 }
                             }
                         },
                         GameStatisticsView::Robber => {
-                            match &mut game.robber {
-                                Robber::Random(_) => {
-                                    ui.label(RichText::new("Random robber").strong());
+                            match game.robber.as_any_mut().downcast_mut::<MenaceRobber>() {
+                                None => {
+                                    if let Some(robber) = game.robber.as_any().downcast_ref::<QLearningRobber>() {
+                                        show_qlearning_robber(ui, robber, robber_bag_viewing_state.as_mut().unwrap(), &game.graph, number_of_cops);
+                                    } else {
+                                        ui.label(RichText::new(format!("{robber_name} robber")).strong());
+                                        if let Some(robber) = game.robber.as_any().downcast_ref::<OptimalRobber>() {
+                                            show_solution(ui, robber.solution(), game.number_of_steps);
+                                        }
+                                    }
                                 }
-                                Robber::Menace(robber) => {
+                                Some(robber) => {
                                     ui.label(RichText::new("Menace robber").strong());
+                                    ui.label(format!(
+                                        "Symmetries: {}",
+                                        robber.symmetries(&game.graph).number_of_automorphisms()
+                                    ));
+                                    ui.label(format!("Bags: {}", robber.bags.len()));
 
                                     ui.add_space(10.0);
 
-                                    let MenaceRobberViewingState {
+                                    let RobberBagViewingState {
                                         bag_key,
                                         editing_vertex,
                                         sort_by_counts,
-                                    } = menace_robber_viewing_state.as_mut().unwrap();
-
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Bag:").strong());
-
-                                        // start_bag is a temporary bool we can change,
-                                        // we use it to change whether we're currently viewing
-                                        // a start bag or a non start bag.
-                                        let mut start_bag = bag_key.1.is_none();
-                                        let selected_text =
-                                            if start_bag { "Start" } else { "Non start" };
-                                        egui::ComboBox::from_id_source("Robber bag type")
-                                            .selected_text(selected_text)
-                                            .show_ui(ui, |ui| {
-                                                ui.selectable_value(&mut start_bag, true, "Start");
-                                                ui.selectable_value(
-                                                    &mut start_bag,
-                                                    false,
-                                                    "Non start",
-                                                );
-                                            });
-                                        // if start_bag changes, we update menace_cop_viewing_bag
-                                        if start_bag {
-                                            if bag_key.1.is_some() {
-                                                *bag_key = (vec![0; number_of_cops as usize], None);
-                                                *editing_vertex = MenaceEditingVertex::None;
-                                            }
-                                        } else if bag_key.1.is_none() {
-                                            *bag_key = (vec![0; number_of_cops as usize], Some(0));
-                                            *editing_vertex = MenaceEditingVertex::None;
-                                        }
-                                    });
+                                        brain_file,
+                                    } = robber_bag_viewing_state.as_mut().unwrap();
 
-                                    // We allow the user to select the bag.
-                                    let cops = &mut bag_key.0;
-                                    ui.horizontal(|ui| {
-                                        ui.selectable_value(
-                                            editing_vertex,
-                                            MenaceEditingVertex::None,
-                                            "View",
-                                        );
-                                        for i in 0..cops.len() {
-                                            ui.selectable_value(
-                                                editing_vertex,
-                                                MenaceEditingVertex::Cop(i),
-                                                format!("Edit cop {i}"),
-                                            );
-                                        }
-                                        if bag_key.1.is_some() {
-                                            ui.selectable_value(
-                                                editing_vertex,
-                                                MenaceEditingVertex::Robber,
-                                                "Edit robber",
-                                            );
-                                        }
-                                    });
-                                    match editing_vertex {
-                                        MenaceEditingVertex::None => {
-                                            show_graph_with_cops_and_robber(
-                                                ui,
-                                                Some(cops),
-                                                bag_key.1,
-                                                &game.graph,
-                                                300.0,
-                                            );
-                                        }
-                                        MenaceEditingVertex::Cop(i) => {
-                                            select_graph_vertex(
-                                                ui,
-                                                &mut cops[*i],
-                                                &game.graph,
-                                                true,
-                                            );
-                                        }
-                                        MenaceEditingVertex::Robber => {
-                                            if let Some(robber) = &mut bag_key.1 {
-                                                select_graph_vertex(
-                                                    ui,
-                                                    robber,
-                                                    &game.graph,
-                                                    false,
-                                                );
-                                            } else {
-                                                // Shouldn't be editing robber, so we change editing vertex.
-                                                *editing_vertex = MenaceEditingVertex::None;
-                                            }
-                                        }
+                                    if let Some(brain) = show_brain_file(ui, brain_file, || robber.brain(&game.graph)) {
+                                        brain_file.message = Some(match robber.load_brain(&game.graph, brain) {
+                                            Ok(()) => "Imported the brain.".to_string(),
+                                            Err(error) => error,
+                                        });
                                     }
 
                                     ui.add_space(10.0);
 
+                                    show_robber_bag_selection(ui, bag_key, editing_vertex, &game.graph, number_of_cops);
+
+                                    ui.add_space(10.0);
+
                                     ui.horizontal(|ui| {
                                         ui.label(RichText::new("Moves:").strong());
                                         ui.toggle_value(sort_by_counts, "Sort moves");
                                     });
 
-                                    let bag = robber.bags.get(bag_key);
-                                    let cops = &bag_key.0;
-                                    if let Some(robber) = bag_key.1 {
-                                        // Non start position
-                                        match bag {
-                                            None => {
-                                                let neighbours = &game.graph.adjacency_list[robber];
-                                                for &neighbour in neighbours {
-                                                    ui.horizontal(|ui| {
-                                                        show_graph_with_cops_and_robber(
-                                                            ui,
-                                                            Some(cops),
-                                                            Some(neighbour),
-                                                            &game.graph,
-                                                            180.0,
-                                                        );
-                                                        ui.label("50");
-                                                    });
-                                                }
-                                                ui.horizontal(|ui| {
-                                                    show_graph_with_cops_and_robber(
-                                                        ui,
-                                                        Some(cops),
-                                                        Some(robber),
-                                                        &game.graph,
-                                                        180.0,
-                                                    );
-                                                    ui.label("50");
-                                                });
-                                            }
-                                            Some(bag) => {
-                                                let positions_and_counts = bag
-                                                    .counts
-                                                    .iter()
-                                                    .enumerate()
-                                                    .map(|(choice, count)| {
-                                                        let neighbours = &game.graph.adjacency_list[robber];
-                                                        let position = if choice == neighbours.len() {
-                                                            robber
-                                                        } else {
-                                                            neighbours[choice]
-                                                        };
-                                                        (position, count)
-                                                    });
-                                                if *sort_by_counts {
-                                                    let mut positions_and_counts =
-                                                        positions_and_counts
-                                                            .collect::<Vec<_>>();
-                                                    positions_and_counts
-                                                        .sort_by_key(|(_, count)| **count);
-                                                    for &(position, count) in
-                                                        positions_and_counts.iter().rev()
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(cops),
-                                                                Some(position),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                } else {
-                                                    for (position, count) in
-                                                        positions_and_counts
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(cops),
-                                                                Some(position),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
+                                    let bag = robber.bag(&game.graph, &bag_key.0, bag_key.1);
+                                    let moves = if *sort_by_counts {
+                                        bag.most_tokens(MAX_SHOWN_MOVES)
                                     } else {
-                                        match bag {
-                                            None => {
-                                                for position in 0..game.graph.vertices.len() {
-                                                    ui.horizontal(|ui| {
-                                                        show_graph_with_cops_and_robber(
-                                                            ui,
-                                                            Some(cops),
-                                                            Some(position),
-                                                            &game.graph,
-                                                            180.0,
-                                                        );
-                                                        ui.label("50");
-                                                    });
-                                                }
-                                            }
-                                            Some(bag) => {
-                                                let positions_and_counts = bag
-                                                    .counts
-                                                    .iter()
-                                                    .enumerate();
-                                                if *sort_by_counts {
-                                                    let mut positions_and_counts =
-                                                        positions_and_counts
-                                                            .collect::<Vec<_>>();
-                                                    positions_and_counts
-                                                        .sort_by_key(|(_, count)| **count);
-                                                    for (position, count) in
-                                                        positions_and_counts.iter().rev()
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(cops),
-                                                                Some(*position),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                } else {
-                                                    for (position, count) in
-                                                        positions_and_counts
-                                                    {
-                                                        ui.horizontal(|ui| {
-                                                            show_graph_with_cops_and_robber(
-                                                                ui,
-                                                                Some(cops),
-                                                                Some(position),
-                                                                &game.graph,
-                                                                180.0,
-                                                            );
-                                                            ui.label(count.to_string());
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
+                                        bag.counts().take(MAX_SHOWN_MOVES).collect()
+                                    };
+                                    for (choice, count) in moves {
+                                        let new_robber_position = robber.decode_move(&game.graph, &bag_key.0, bag_key.1, choice);
+                                        ui.horizontal(|ui| {
+                                            show_graph_with_cops_and_robber(
+                                                ui,
+                                                Some(&bag_key.0),
+                                                Some(new_robber_position),
+                                                &game.graph,
+                                                180.0,
+                                            );
+                                            ui.label(count.to_string());
+                                        });
                                     }
+                                    show_number_of_hidden_moves(ui, bag.number_of_moves());
                                 }
                             }
                         },
@@ -1529,6 +3196,43 @@ fn game_details(
                                             plot_ui.line(half_line)
                                         });
                             ui.label("The fraction of cop wins (evaluated after every perfect square number of matches).");
+
+                            ui.add_space(10.0);
+
+                            let statistics = || GameStatistics {
+                                graph: &game.graph,
+                                seed: game.seed,
+                                number_of_cops,
+                                number_of_steps: game.number_of_steps,
+                                cop: cop_name,
+                                robber: robber_name,
+                                cop_settings,
+                                robber_settings,
+                                score: game.score,
+                                matches: match_results,
+                                cop_scores: cop_scores
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(i, &cop_wins)| CopScore {
+                                        matches: (i as u32 + 1).pow(2),
+                                        cop_wins,
+                                    })
+                                    .collect(),
+                            };
+                            show_statistics_file(ui, statistics_path, statistics_message, statistics);
+                        }
+                        GameStatisticsView::Replay => {
+                            show_replay(ui, replay_viewing_state, game, *speed, |game, record| Replay {
+                                graph: &game.graph,
+                                seed: game.seed,
+                                number_of_cops,
+                                number_of_steps: game.number_of_steps,
+                                cop: cop_name,
+                                robber: robber_name,
+                                cop_settings,
+                                robber_settings,
+                                record,
+                            });
                         }
                     }
                 }
@@ -1536,10 +3240,213 @@ fn game_details(
         });
 }
 
+// Shows the cop win rates of every pairing, with a row for every cop and a column for every robber.
+fn show_tournament_results(
+    ui: &mut egui::Ui,
+    id: &str,
+    tournament: &Tournament,
+    cop_win_rate: impl Fn(usize, usize) -> Option<f64>,
+) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        ui.label(RichText::new("Cop \\ Robber").strong());
+        for robber in &tournament.robbers {
+            ui.label(RichText::new(robber).strong());
+        }
+        ui.end_row();
+
+        for (cop_index, cop) in tournament.cops.iter().enumerate() {
+            ui.label(RichText::new(cop).strong());
+            for robber_index in 0..tournament.robbers.len() {
+                match cop_win_rate(cop_index, robber_index) {
+                    Some(cop_win_rate) => ui.label(format!("{cop_win_rate:.2}")),
+                    None => ui.label("-"),
+                };
+            }
+            ui.end_row();
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn tournament(
+    ctx: &egui::Context,
+    tournament_view_state: &mut TournamentViewState,
+    graphs: &[Graph],
+    number_of_cops: u8,
+    number_of_steps: u8,
+    cop_settings: &StrategySettings,
+    robber_settings: &StrategySettings,
+    seed: &mut u64,
+    random_seed: bool,
+    registry: &StrategyRegistry,
+) -> Option<View> {
+    let mut view = None;
+
+    let TournamentViewState {
+        selected_graphs,
+        cops,
+        robbers,
+        number_of_matches,
+        reset_learners,
+        handle,
+    } = tournament_view_state;
+
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.heading("Cops and Robbers");
+
+        if ui.button("Back").clicked() {
+            view = Some(View::GameSettingsSelection);
+            return;
+        }
+
+        ui.add_space(5.0);
+
+        ui.label(format!(
+            "Every selected cop algorithm plays every selected robber algorithm with {number_of_cops} \
+            cops and {number_of_steps} steps. The algorithms use the settings from the game settings."
+        ));
+
+        let is_running = handle.as_ref().map_or(false, |handle| !handle.is_finished());
+        // An algorithm can only take part if it can play on every selected graph.
+        let selected_graph_list: Vec<_> = graphs
+            .iter()
+            .zip(selected_graphs.iter())
+            .filter(|(_, selected)| **selected)
+            .map(|(graph, _)| graph)
+            .collect();
+        let check_cop = |name: &str| {
+            selected_graph_list
+                .iter()
+                .try_for_each(|graph| registry.check_cop(name, graph, number_of_cops))
+        };
+        let check_robber = |name: &str| {
+            selected_graph_list
+                .iter()
+                .try_for_each(|graph| registry.check_robber(name, graph, number_of_cops))
+        };
+        let show_algorithm = |ui: &mut egui::Ui, name: &str, selected: &mut bool, check| {
+            match check {
+                Ok(()) => {
+                    ui.checkbox(selected, name);
+                }
+                Err(error) => {
+                    ui.add_enabled(false, egui::Checkbox::new(&mut false, name))
+                        .on_disabled_hover_text(error);
+                }
+            }
+        };
+        ui.add_enabled_ui(!is_running, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Graphs");
+                for (graph, selected) in graphs.iter().zip(selected_graphs.iter_mut()) {
+                    ui.checkbox(selected, &graph.name);
+                }
+            });
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Cops");
+                for (name, selected) in cops.iter_mut() {
+                    show_algorithm(ui, name, selected, check_cop(name));
+                }
+            });
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Robbers");
+                for (name, selected) in robbers.iter_mut() {
+                    show_algorithm(ui, name, selected, check_robber(name));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Matches per pairing");
+                ui.add(egui::DragValue::new(number_of_matches).clamp_range(1..=100_000));
+            });
+            ui.checkbox(reset_learners, "Reset learning players between pairings")
+                .on_hover_text(
+                    "Otherwise MENACE and Q-learning players keep what they learned \
+                    against their previous opponents on the same graph",
+                );
+        });
+
+        ui.horizontal(|ui| {
+            if is_running {
+                if ui.button("Stop").clicked() {
+                    *handle = None;
+                }
+                ui.spinner();
+            } else if ui.button("Start").clicked() {
+                if random_seed {
+                    *seed = rand::random();
+                }
+                let selected = |algorithms: &[(String, bool)], check: &dyn Fn(&str) -> Result<(), String>| {
+                    algorithms
+                        .iter()
+                        .filter(|(name, selected)| *selected && check(name).is_ok())
+                        .map(|(name, _)| name.clone())
+                        .collect()
+                };
+                let tournament = Tournament {
+                    graphs: selected_graph_list.iter().map(|&graph| graph.clone()).collect(),
+                    cops: selected(cops, &check_cop),
+                    robbers: selected(robbers, &check_robber),
+                    number_of_cops,
+                    number_of_steps,
+                    number_of_matches: *number_of_matches,
+                    cop_settings: cop_settings.clone(),
+                    robber_settings: robber_settings.clone(),
+                    reset_learners: *reset_learners,
+                    seed: *seed,
+                };
+                *handle = Some(TournamentHandle::new(
+                    tournament,
+                    registry.clone(),
+                    ctx.clone(),
+                ));
+            }
+        });
+
+        let Some(handle) = handle else {
+            return;
+        };
+        let tournament = &handle.tournament;
+        let results = handle.results.lock().clone();
+
+        ui.label(format!(
+            "Played {} of {} pairings with the seed {}.",
+            results.number_of_played_pairings(),
+            results.number_of_pairings(),
+            tournament.seed
+        ));
+        if let Some(error) = &*handle.error.lock() {
+            ui.label(error);
+        }
+
+        ui.add_space(10.0);
+
+        egui::ScrollArea::both().show(ui, |ui| {
+            if tournament.graphs.len() > 1 {
+                ui.label(RichText::new("All graphs").strong());
+                show_tournament_results(ui, "All graphs", tournament, |cop, robber| {
+                    results.total_cop_win_rate(cop, robber)
+                });
+                ui.add_space(10.0);
+            }
+            for (graph_index, graph) in tournament.graphs.iter().enumerate() {
+                ui.label(RichText::new(&graph.name).strong());
+                let id = format!("Tournament graph {graph_index}");
+                show_tournament_results(ui, &id, tournament, |cop, robber| {
+                    results.cop_win_rate(graph_index, cop, robber)
+                });
+                ui.add_space(10.0);
+            }
+            ui.label("The fraction of matches won by the cops in every pairing.");
+        });
+    });
+
+    view
+}
+
 fn game(
     ctx: &egui::Context,
     game_handle: &mut GameHandle,
-    graphs: &Vec<Graph>,
+    graphs: &[Graph],
     current_graph: usize,
     number_of_cops: u8,
 ) -> Option<View> {
@@ -1547,43 +3454,71 @@ fn game(
 
     let GameHandle {
         game_view_state: game_and_animation_state,
-        number_of_immediate_games,
+        batch,
+        batch_size,
+        has_human_player,
         ..
     } = game_handle;
 
-    game_details(
-        ctx,
-        game_and_animation_state,
-        number_of_immediate_games,
-        number_of_cops,
-    );
+    game_details(ctx, game_and_animation_state, number_of_cops);
 
     egui::CentralPanel::default().show(ctx, |ui| {
         ui.heading("Cops and Robbers");
 
+        // We copy the batch, so we don't hold its lock while we lock the game.
+        let current_batch = *batch.lock();
+
         ui.horizontal(|ui| {
             if ui.button("Create new game").clicked() {
                 view = Some(View::GameSettingsSelection);
                 return;
             }
 
-            if ui.button("Play 1000 games").clicked() {
-                let mut number_of_immediate_games = number_of_immediate_games.lock();
-                *number_of_immediate_games = match *number_of_immediate_games {
-                    Some(games) => Some(games + 1000),
-                    None => Some(1000),
-                };
+            if !*has_human_player && current_batch.is_none() {
+                if ui.button("Play").clicked() {
+                    *batch.lock() = Some(Batch {
+                        number_of_games: *batch_size,
+                        games_played: 0,
+                        cop_wins: 0,
+                        cancelled: false,
+                    });
+                }
+                ui.add(
+                    egui::DragValue::new(batch_size)
+                        .clamp_range(1..=MAX_BATCH_SIZE)
+                        .suffix(" games"),
+                );
             }
         });
 
-        let number_of_immediate_games = number_of_immediate_games.lock();
-        if number_of_immediate_games.is_some() {
-            ui.spinner();
-            show_graph(ui, &graphs[current_graph]);
-        } else {
-            let mut game_and_animation_state = game_and_animation_state.lock();
-            if let Some(game_and_animation_state) = &mut (*game_and_animation_state) {
-                show_game(ui, &graphs[current_graph], game_and_animation_state);
+        if let Some(current_batch) = current_batch {
+            show_batch_progress(ui, &current_batch, || {
+                if let Some(batch) = &mut (*batch.lock()) {
+                    batch.cancelled = true;
+                }
+            });
+        }
+
+        let mut game_and_animation_state = game_and_animation_state.lock();
+        if let Some(game_and_animation_state) = &mut (*game_and_animation_state) {
+            if current_batch.is_some() {
+                // The positions change too fast to animate while the batch is played.
+                show_score(ui, &game_and_animation_state.game);
+                show_graph(ui, &graphs[current_graph]);
+            } else {
+                show_game_controls(ui, game_and_animation_state);
+                if game_and_animation_state.waiting_for_human
+                    && game_and_animation_state.is_waiting_for_human()
+                {
+                    show_human_move(
+                        ui,
+                        &graphs[current_graph],
+                        game_and_animation_state,
+                        number_of_cops,
+                    );
+                } else {
+                    show_game(ui, &graphs[current_graph], game_and_animation_state);
+                }
             }
         }
     });
@@ -1594,17 +3529,30 @@ fn game(
 impl TemplateApp {
     /// Called once before the first frame.
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::with_registry(cc, StrategyRegistry::default())
+    }
+
+    /// Like [`TemplateApp::new`], but the algorithms that can be selected
+    /// in the game settings are taken from `registry`.
+    pub fn with_registry(cc: &eframe::CreationContext<'_>, registry: StrategyRegistry) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
         cc.egui_ctx.set_visuals(egui::Visuals::light());
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+        let mut app: TemplateApp = if let Some(storage) = cc.storage {
+            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
+        } else {
+            Default::default()
+        };
+        app.registry = registry;
+        // We always need a graph to play on, even if the saved state has none.
+        if app.graphs.is_empty() {
+            app.graphs = template_graphs();
         }
-
-        Default::default()
+        app.current_graph = app.current_graph.min(app.graphs.len() - 1);
+        app
     }
 }
 
@@ -1622,8 +3570,14 @@ impl eframe::App for TemplateApp {
             current_graph,
             number_of_cops,
             number_of_steps,
-            cop,
-            robber,
+            cop_algorithm,
+            robber_algorithm,
+            cop_settings,
+            robber_settings,
+            seed,
+            random_seed,
+            registry,
+            graph_analysis,
             view,
         } = self;
 
@@ -1634,15 +3588,42 @@ impl eframe::App for TemplateApp {
                 current_graph,
                 number_of_cops,
                 number_of_steps,
-                cop,
-                robber,
+                cop_algorithm,
+                robber_algorithm,
+                cop_settings,
+                robber_settings,
+                seed,
+                random_seed,
+                registry,
+                graph_analysis,
             ),
             View::GraphCreation(graph_creation_state) => {
                 graph_creation(ctx, graph_creation_state, graphs, current_graph)
             }
+            View::GraphGeneration(graph_generation_state) => {
+                graph_generation(ctx, graph_generation_state, graphs, current_graph)
+            }
+            View::GraphFile(graph_file_state) => {
+                graph_file(ctx, graph_file_state, graphs, current_graph)
+            }
+            View::GraphManager(graph_manager_state) => {
+                graph_manager(ctx, graph_manager_state, graphs, current_graph)
+            }
             View::Game(game_handle) => {
                 game(ctx, game_handle, graphs, *current_graph, *number_of_cops)
             }
+            View::Tournament(tournament_view_state) => tournament(
+                ctx,
+                tournament_view_state,
+                graphs,
+                *number_of_cops,
+                *number_of_steps,
+                cop_settings,
+                robber_settings,
+                seed,
+                *random_seed,
+                registry,
+            ),
         };
 
         if let Some(new_view) = new_view {
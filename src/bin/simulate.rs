@@ -0,0 +1,285 @@
+#![warn(clippy::all, rust_2018_idioms)]
+
+// Plays matches without a window and prints the win statistics.
+// Run `simulate --help` for the options.
+
+use egui_cops_and_robbers::{
+    formats::{import_graph, GraphFormat},
+    game::{
+        template_graphs, Game, Graph, MenaceSettings, StrategyRegistry, StrategySettings, Turn,
+    },
+    heuristics::{EvasiveSettings, TieBreaking},
+    human::{HumanCop, HumanRobber},
+    mcts::MctsSettings,
+    qlearning::QLearningSettings,
+};
+
+const USAGE: &str = "Usage: simulate --graph <template name or graph file> [options]
+
+Options:
+    --graph <graph>      A template graph name (Path2, Path5, Hexagon) or a graph file
+                         (.json, .dot, .graphml, .g6, .s6 or an edge list)
+    --cop <algorithm>    The cop algorithm (default: Random)
+    --robber <algorithm> The robber algorithm (default: Random)
+    --cops <number>      The number of cops (default: 1)
+    --steps <number>     The number of steps (default: 1)
+    --matches <number>   The number of matches to play (default: 1000)
+    --seed <number>      The seed of the random number generator (default: random)
+    --help               Print this message
+
+MENACE options, given separately for each side with the prefix --cop- or --robber-:
+    --cop-initial-tokens <number>         Tokens for each move in a new bag (default: 50)
+    --cop-win-reward <number>             Tokens added for each move of a won match (default: 3)
+    --cop-loss-reward <number>            Tokens added for each move of a lost match (default: -1)
+    --cop-draw-reward <number>            Reward escapes until the steps run out as draws
+    --cop-scale-by-closeness <true|false> Scale rewards by closeness to the end (default: false)
+    --cop-symmetries <true|false>         Share bags between symmetric positions (default: true)
+
+Q-learning options, with the same prefixes (--cop-symmetries applies to Q-tables too):
+    --cop-learning-rate <number>          Step size of each update, 0 to 1 (default: 0.1)
+    --cop-discount <number>               Weight of the next position's value, 0 to 1 (default: 0.9)
+    --cop-epsilon <number>                Probability of a random move, 0 to 1 (default: 0.1)
+
+MCTS options, with the same prefixes:
+    --cop-rollouts <number>               Rollouts for every move (default: 1000)
+    --cop-horizon <number>                Robber moves the search looks ahead (default: 10)
+
+Evasive robber options:
+    --robber-tie-breaking <rule>          How to choose between moves equally far from the
+                                          nearest cop: random, total-distance or
+                                          most-neighbours (default: random)";
+
+struct Options {
+    graph: Graph,
+    cop: String,
+    robber: String,
+    number_of_cops: u8,
+    number_of_steps: u8,
+    number_of_matches: u32,
+    seed: u64,
+    cop_settings: StrategySettings,
+    robber_settings: StrategySettings,
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {option}: {value}"))
+}
+
+// Parses an algorithm option without its --cop- or --robber- prefix.
+// Returns false if it isn't an algorithm option.
+fn parse_strategy_option(
+    settings: &mut StrategySettings,
+    name: &str,
+    option: &str,
+    value: &str,
+) -> Result<bool, String> {
+    // The settings of the built in algorithms, by their registry names.
+    let mut menace: MenaceSettings = settings.get("Menace");
+    let mut qlearning: QLearningSettings = settings.get("Q-learning");
+    let mut mcts: MctsSettings = settings.get("MCTS");
+    let mut evasive: EvasiveSettings = settings.get("Evasive");
+    match name {
+        "initial-tokens" => {
+            menace.initial_tokens = parse_value(option, value)?;
+            if menace.initial_tokens == 0 {
+                return Err(format!("{option} must be at least 1"));
+            }
+        }
+        "win-reward" => menace.win_reward = parse_value(option, value)?,
+        "loss-reward" => menace.loss_reward = parse_value(option, value)?,
+        "draw-reward" => menace.draw_reward = Some(parse_value(option, value)?),
+        "scale-by-closeness" => menace.scale_by_closeness = parse_value(option, value)?,
+        "symmetries" => {
+            menace.use_symmetries = parse_value(option, value)?;
+            qlearning.use_symmetries = menace.use_symmetries;
+        }
+        "learning-rate" | "discount" | "epsilon" => {
+            let parameter: f64 = parse_value(option, value)?;
+            if !(0.0..=1.0).contains(&parameter) {
+                return Err(format!("{option} must be between 0 and 1"));
+            }
+            match name {
+                "learning-rate" => qlearning.learning_rate = parameter,
+                "discount" => qlearning.discount = parameter,
+                _ => qlearning.epsilon = parameter,
+            }
+        }
+        "rollouts" => {
+            mcts.rollouts = parse_value(option, value)?;
+            if mcts.rollouts == 0 {
+                return Err(format!("{option} must be at least 1"));
+            }
+        }
+        "horizon" => {
+            mcts.horizon = parse_value(option, value)?;
+            if mcts.horizon == 0 {
+                return Err(format!("{option} must be at least 1"));
+            }
+        }
+        "tie-breaking" => {
+            evasive.tie_breaking = match value {
+                "random" => TieBreaking::Random,
+                "total-distance" => TieBreaking::TotalDistance,
+                "most-neighbours" => TieBreaking::MostNeighbours,
+                _ => return Err(format!("Invalid value for {option}: {value}")),
+            }
+        }
+        _ => return Ok(false),
+    }
+    settings.set("Menace", &menace);
+    settings.set("Q-learning", &qlearning);
+    settings.set("MCTS", &mcts);
+    settings.set("Evasive", &evasive);
+    Ok(true)
+}
+
+fn load_graph(name_or_path: &str) -> Result<Graph, String> {
+    if let Some(graph) = template_graphs()
+        .into_iter()
+        .find(|graph| graph.name == name_or_path)
+    {
+        return Ok(graph);
+    }
+
+    let contents = std::fs::read_to_string(name_or_path)
+        .map_err(|error| format!("Couldn't read graph {name_or_path}: {error}"))?;
+    // Files with an unknown extension are read as edge lists.
+    let format = GraphFormat::from_path(name_or_path).unwrap_or(GraphFormat::EdgeList);
+    import_graph(&contents, format, name_or_path)
+        .map_err(|error| format!("Couldn't parse graph {name_or_path}: {error}"))
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut graph = None;
+    let mut options = Options {
+        graph: Graph::default(),
+        cop: "Random".to_string(),
+        robber: "Random".to_string(),
+        number_of_cops: 1,
+        number_of_steps: 1,
+        number_of_matches: 1000,
+        seed: rand::random(),
+        cop_settings: StrategySettings::default(),
+        robber_settings: StrategySettings::default(),
+    };
+
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {option}\n\n{USAGE}"))?;
+        match option.as_str() {
+            "--graph" => graph = Some(load_graph(&value)?),
+            "--cop" => options.cop = value,
+            "--robber" => options.robber = value,
+            "--cops" => options.number_of_cops = parse_value(&option, &value)?,
+            "--steps" => options.number_of_steps = parse_value(&option, &value)?,
+            "--matches" => options.number_of_matches = parse_value(&option, &value)?,
+            "--seed" => options.seed = parse_value(&option, &value)?,
+            _ => {
+                let parsed = if let Some(name) = option.strip_prefix("--cop-") {
+                    parse_strategy_option(&mut options.cop_settings, name, &option, &value)?
+                } else if let Some(name) = option.strip_prefix("--robber-") {
+                    parse_strategy_option(&mut options.robber_settings, name, &option, &value)?
+                } else {
+                    false
+                };
+                if !parsed {
+                    return Err(format!("Unknown option {option}\n\n{USAGE}"));
+                }
+            }
+        }
+    }
+
+    options.graph = graph.ok_or_else(|| format!("Missing --graph\n\n{USAGE}"))?;
+    if options.number_of_cops == 0 {
+        return Err("There must be at least one cop".to_string());
+    }
+    Ok(options)
+}
+
+fn main() {
+    if std::env::args().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return;
+    }
+
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    let registry = StrategyRegistry::default();
+    let Some(cop) =
+        registry.create_cop(&options.cop, options.number_of_cops, &options.cop_settings)
+    else {
+        let names: Vec<_> = registry.cop_names().collect();
+        eprintln!(
+            "Unknown cop algorithm {}. Choose one of: {}",
+            options.cop,
+            names.join(", ")
+        );
+        std::process::exit(2);
+    };
+    let Some(robber) = registry.create_robber(
+        &options.robber,
+        options.number_of_cops,
+        &options.robber_settings,
+    ) else {
+        let names: Vec<_> = registry.robber_names().collect();
+        eprintln!(
+            "Unknown robber algorithm {}. Choose one of: {}",
+            options.robber,
+            names.join(", ")
+        );
+        std::process::exit(2);
+    };
+    if cop.as_any().is::<HumanCop>() || robber.as_any().is::<HumanRobber>() {
+        eprintln!("Human players can only play in the app");
+        std::process::exit(2);
+    }
+
+    let check = registry
+        .check_cop(&options.cop, &options.graph, options.number_of_cops)
+        .and(registry.check_robber(&options.robber, &options.graph, options.number_of_cops));
+    if let Err(error) = check {
+        eprintln!("{error}");
+        std::process::exit(2);
+    }
+
+    let mut game = Game::new(
+        &options.graph,
+        options.number_of_steps,
+        cop,
+        robber,
+        options.seed,
+    );
+    let mut matches_played = 0;
+    while matches_played < options.number_of_matches {
+        game.update();
+        if game.turn == Turn::Over {
+            matches_played += 1;
+        }
+    }
+
+    let [cop_wins, robber_wins] = game.score;
+    println!("Graph: {}", options.graph.name);
+    println!(
+        "Cop: {} ({} cops), Robber: {}, Steps: {}",
+        options.cop, options.number_of_cops, options.robber, options.number_of_steps
+    );
+    println!("Seed: {}", options.seed);
+    println!("Matches: {matches_played}");
+    println!("Cop wins: {cop_wins}");
+    println!("Robber wins: {robber_wins}");
+    if matches_played > 0 {
+        println!(
+            "Cop win fraction: {:.4}",
+            cop_wins as f64 / matches_played as f64
+        );
+    }
+}
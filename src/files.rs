@@ -0,0 +1,110 @@
+// Saving and loading files. Natively we use paths on the file system.
+// On the web we download saved files, and load a file chosen by the user with a file picker.
+
+use egui::mutex::Mutex;
+use std::sync::Arc;
+
+/// A file which is being loaded. On the web the user has to choose the file
+/// and it's read asynchronously, so we poll it every frame.
+pub struct PendingFile {
+    contents: Arc<Mutex<Option<Result<String, String>>>>,
+}
+
+impl PendingFile {
+    /// The contents of the file, once it has been loaded.
+    pub fn take(&self) -> Option<Result<String, String>> {
+        self.contents.lock().take()
+    }
+}
+
+/// Saves the file. On the web the file is downloaded with the file name of `path`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_file(path: &str, contents: &str) -> Result<(), String> {
+    std::fs::write(path, contents).map_err(|error| format!("Couldn't save {path}: {error}"))
+}
+
+/// Loads the file. On the web the user chooses the file, so `path` isn't used.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_file(path: &str, _ctx: &egui::Context) -> PendingFile {
+    let contents =
+        std::fs::read_to_string(path).map_err(|error| format!("Couldn't load {path}: {error}"));
+    PendingFile {
+        contents: Arc::new(Mutex::new(Some(contents))),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save_file(path: &str, contents: &str) -> Result<(), String> {
+    use wasm_bindgen::JsCast;
+
+    let error = |_| format!("Couldn't save {path}");
+
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let blob = web_sys::Blob::new_with_str_sequence(&parts).map_err(error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(error)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| format!("Couldn't save {path}"))?;
+    let anchor: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(error)?
+        .dyn_into()
+        .map_err(|_| format!("Couldn't save {path}"))?;
+    anchor.set_href(&url);
+    anchor.set_download(path);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(error)?;
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load_file(_path: &str, ctx: &egui::Context) -> PendingFile {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let pending_file = PendingFile {
+        contents: Arc::new(Mutex::new(None)),
+    };
+    let contents = Arc::clone(&pending_file.contents);
+    let set_error = |contents: &Arc<Mutex<Option<Result<String, String>>>>| {
+        *contents.lock() = Some(Err("Couldn't open the file picker".to_string()));
+    };
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        set_error(&contents);
+        return pending_file;
+    };
+    let Some(input) = document
+        .create_element("input")
+        .ok()
+        .and_then(|element| element.dyn_into::<web_sys::HtmlInputElement>().ok())
+    else {
+        set_error(&contents);
+        return pending_file;
+    };
+    input.set_type("file");
+
+    let ctx = ctx.clone();
+    let input_clone = input.clone();
+    let on_change = Closure::once(move || {
+        let Some(file) = input_clone.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        wasm_bindgen_futures::spawn_local(async move {
+            let text = wasm_bindgen_futures::JsFuture::from(file.text()).await;
+            *contents.lock() = Some(
+                text.ok()
+                    .and_then(|text| text.as_string())
+                    .ok_or_else(|| format!("Couldn't load {}", file.name())),
+            );
+            ctx.request_repaint();
+        });
+    });
+    input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+    // The closure has to live until the user chooses a file, which may be never.
+    on_change.forget();
+    input.click();
+
+    pending_file
+}
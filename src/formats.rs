@@ -0,0 +1,1076 @@
+// Reading and writing graphs in the formats of other graph tools, e.g. networkx and Graphviz.
+// Formats without positions (and files without them) get an automatic layout.
+
+use crate::{
+    game::Graph,
+    generators::{adjacency_list_from_edges, circle_points, graph_from_edges},
+    layouts::force_directed,
+};
+use std::collections::HashMap;
+
+// The largest graph we import, so a corrupt file can't make us allocate too much.
+const MAX_IMPORTED_VERTICES: usize = 1_000;
+// The size of the drawing of exported DOT files, in points.
+const DOT_SCALE: f32 = 500.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// The serde JSON of `Graph`, which is also used by the simulator.
+    Json,
+    /// The Graphviz format.
+    Dot,
+    GraphMl,
+    Graph6,
+    Sparse6,
+    /// A line with the two vertices of every edge.
+    EdgeList,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 6] = [
+        GraphFormat::Json,
+        GraphFormat::Dot,
+        GraphFormat::GraphMl,
+        GraphFormat::Graph6,
+        GraphFormat::Sparse6,
+        GraphFormat::EdgeList,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GraphFormat::Json => "JSON",
+            GraphFormat::Dot => "DOT",
+            GraphFormat::GraphMl => "GraphML",
+            GraphFormat::Graph6 => "graph6",
+            GraphFormat::Sparse6 => "sparse6",
+            GraphFormat::EdgeList => "Edge list",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            GraphFormat::Json => "json",
+            GraphFormat::Dot => "dot",
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Graph6 => "g6",
+            GraphFormat::Sparse6 => "s6",
+            GraphFormat::EdgeList => "edgelist",
+        }
+    }
+
+    /// The format of a file with the extension of the path, if it's a known one.
+    pub fn from_path(path: &str) -> Option<GraphFormat> {
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_lowercase().as_str() {
+            "gv" => Some(GraphFormat::Dot),
+            "txt" => Some(GraphFormat::EdgeList),
+            extension => GraphFormat::ALL
+                .into_iter()
+                .find(|format| format.extension() == extension),
+        }
+    }
+}
+
+pub fn export_graph(graph: &Graph, format: GraphFormat) -> Result<String, String> {
+    match format {
+        GraphFormat::Json => serde_json::to_string(graph).map_err(|error| error.to_string()),
+        GraphFormat::Dot => Ok(export_dot(graph)),
+        GraphFormat::GraphMl => Ok(export_graphml(graph)),
+        GraphFormat::Graph6 => Ok(export_graph6(graph)),
+        GraphFormat::Sparse6 => Ok(export_sparse6(graph)),
+        GraphFormat::EdgeList => Ok(export_edge_list(graph)),
+    }
+}
+
+/// Reads a graph. The name is used if the file doesn't name the graph.
+pub fn import_graph(contents: &str, format: GraphFormat, name: &str) -> Result<Graph, String> {
+    let imported = match format {
+        GraphFormat::Json => {
+            let mut graph: Graph = serde_json::from_str(contents)
+                .map_err(|error| format!("Couldn't read the graph: {error}"))?;
+            check_graph(&graph)?;
+            check_number_of_vertices(graph.vertices.len())?;
+            // We keep the coordinates, but make every edge go both ways and leave out loops
+            // and repeated edges, like the other formats.
+            let edges: Vec<_> = (graph.adjacency_list.iter().enumerate())
+                .flat_map(|(v, neighbours)| neighbours.iter().map(move |&u| (v, u)))
+                .collect();
+            graph.adjacency_list = adjacency_list_from_edges(graph.vertices.len(), edges);
+            return Ok(graph);
+        }
+        GraphFormat::Dot => import_dot(contents)?,
+        GraphFormat::GraphMl => import_graphml(contents)?,
+        GraphFormat::Graph6 | GraphFormat::Sparse6 => import_graph6(contents)?,
+        GraphFormat::EdgeList => import_edge_list(contents)?,
+    };
+    let ImportedGraph {
+        number_of_vertices,
+        edges,
+        positions,
+        name: imported_name,
+    } = imported;
+    if number_of_vertices == 0 {
+        return Err("The graph has no vertices.".to_string());
+    }
+    check_number_of_vertices(number_of_vertices)?;
+    let name = imported_name
+        .filter(|imported_name| !imported_name.is_empty())
+        .unwrap_or_else(|| name.to_string());
+    // Positions are only used if every vertex has one. Otherwise the graph is laid out by
+    // the force-directed layout, starting from a circle.
+    match positions.into_iter().collect::<Option<Vec<_>>>() {
+        Some(vertices) if vertices.len() == number_of_vertices => {
+            Ok(graph_from_edges(name, vertices, edges))
+        }
+        _ => {
+            let vertices = circle_points(number_of_vertices, 1.0, 0.0);
+            let mut graph = graph_from_edges(name, vertices, edges);
+            force_directed(&mut graph);
+            Ok(graph)
+        }
+    }
+}
+
+/// Checks that a graph read from a file can be played on.
+pub fn check_graph(graph: &Graph) -> Result<(), String> {
+    if graph.vertices.is_empty() || graph.adjacency_list.len() != graph.vertices.len() {
+        return Err(
+            "The graph needs at least one vertex and an adjacency list for each vertex."
+                .to_string(),
+        );
+    }
+    if graph
+        .adjacency_list
+        .iter()
+        .flatten()
+        .any(|&vertex| vertex >= graph.vertices.len())
+    {
+        return Err("The graph has an edge to a missing vertex.".to_string());
+    }
+    if graph
+        .vertices
+        .iter()
+        .any(|&(x, y)| !x.is_finite() || !y.is_finite())
+    {
+        return Err("The graph has a vertex without finite coordinates.".to_string());
+    }
+    Ok(())
+}
+
+fn check_number_of_vertices(number_of_vertices: usize) -> Result<(), String> {
+    if number_of_vertices > MAX_IMPORTED_VERTICES {
+        return Err(format!(
+            "The graph has {number_of_vertices} vertices, more than the {MAX_IMPORTED_VERTICES} we can import."
+        ));
+    }
+    Ok(())
+}
+
+// A graph as read from a file, before it's laid out.
+struct ImportedGraph {
+    number_of_vertices: usize,
+    edges: Vec<(usize, usize)>,
+    // The positions of the vertices, in the coordinates of the file.
+    positions: Vec<Option<(f32, f32)>>,
+    name: Option<String>,
+}
+
+// The edges of a graph, each once with the smaller vertex first.
+fn edges(graph: &Graph) -> impl Iterator<Item = (usize, usize)> + '_ {
+    graph
+        .adjacency_list
+        .iter()
+        .enumerate()
+        .flat_map(|(v, neighbours)| neighbours.iter().map(move |&u| (v, u)))
+        .filter(|&(v, u)| v < u)
+}
+
+// The indices of the vertices of a file, in the order they appear in it.
+#[derive(Default)]
+struct VertexIndices {
+    indices: HashMap<String, usize>,
+}
+
+impl VertexIndices {
+    fn index(&mut self, vertex: &str) -> usize {
+        let next_index = self.indices.len();
+        *self.indices.entry(vertex.to_string()).or_insert(next_index)
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+// DOT
+
+fn quote_dot(string: &str) -> String {
+    format!("\"{}\"", string.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// The positions are in points with the y axis pointing up, as in Graphviz.
+fn export_dot(graph: &Graph) -> String {
+    let mut dot = format!("graph {} {{\n", quote_dot(&graph.name));
+    for (v, &(x, y)) in graph.vertices.iter().enumerate() {
+        dot.push_str(&format!(
+            "  {v} [pos=\"{},{}!\"];\n",
+            x * DOT_SCALE,
+            (1.0 - y) * DOT_SCALE
+        ));
+    }
+    for (v, u) in edges(graph) {
+        dot.push_str(&format!("  {v} -- {u};\n"));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DotToken {
+    Id(String),
+    Symbol(char),
+    // -- or ->, as we read directed graphs as undirected.
+    Edge,
+}
+
+fn dot_tokens(contents: &str) -> Result<Vec<DotToken>, String> {
+    let is_id_char = |c: char| c.is_alphanumeric() || c == '_' || c == '.' || !c.is_ascii();
+    let chars: Vec<char> = contents.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' || (c == '/' && next == Some('/')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' {
+            let mut id = String::new();
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'"') {
+                    i += 1;
+                } else if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                    // A line continuation.
+                    i += 2;
+                    continue;
+                }
+                id.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("The DOT file has an unterminated string.".to_string());
+            }
+            i += 1;
+            tokens.push(DotToken::Id(id));
+        } else if c == '<' {
+            // An HTML string, which ends at the matching '>'.
+            let mut depth = 0;
+            let start = i;
+            while i < chars.len() {
+                match chars[i] {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    _ => {}
+                }
+                i += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            if depth != 0 {
+                return Err("The DOT file has an unterminated HTML string.".to_string());
+            }
+            tokens.push(DotToken::Id(chars[start + 1..i - 1].iter().collect()));
+        } else if c == '-' && (next == Some('-') || next == Some('>')) {
+            tokens.push(DotToken::Edge);
+            i += 2;
+        } else if is_id_char(c) || c == '-' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && is_id_char(chars[i]) {
+                i += 1;
+            }
+            tokens.push(DotToken::Id(chars[start..i].iter().collect()));
+        } else if "{}[];,=:".contains(c) {
+            tokens.push(DotToken::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("The DOT file has an unexpected character '{c}'."));
+        }
+    }
+    Ok(tokens)
+}
+
+// Reads the attribute lists after a statement and returns its attributes.
+fn dot_attributes(tokens: &[DotToken], i: &mut usize) -> Result<HashMap<String, String>, String> {
+    let mut attributes = HashMap::new();
+    while tokens.get(*i) == Some(&DotToken::Symbol('[')) {
+        *i += 1;
+        loop {
+            match tokens.get(*i) {
+                Some(DotToken::Symbol(']')) => {
+                    *i += 1;
+                    break;
+                }
+                Some(DotToken::Symbol(',' | ';')) => *i += 1,
+                Some(DotToken::Id(key)) => {
+                    if tokens.get(*i + 1) != Some(&DotToken::Symbol('=')) {
+                        return Err(format!("The DOT attribute {key} has no value."));
+                    }
+                    let Some(DotToken::Id(value)) = tokens.get(*i + 2) else {
+                        return Err(format!("The DOT attribute {key} has no value."));
+                    };
+                    attributes.insert(key.clone(), value.clone());
+                    *i += 3;
+                }
+                _ => return Err("The DOT file has an unterminated attribute list.".to_string()),
+            }
+        }
+    }
+    Ok(attributes)
+}
+
+// Reads a node ID and skips its port.
+fn dot_node(tokens: &[DotToken], i: &mut usize) -> Result<String, String> {
+    let Some(DotToken::Id(id)) = tokens.get(*i) else {
+        return Err("The DOT file has an edge without a node.".to_string());
+    };
+    *i += 1;
+    while tokens.get(*i) == Some(&DotToken::Symbol(':')) {
+        *i += 2;
+    }
+    Ok(id.clone())
+}
+
+// Reads the nodes and edges of a DOT graph, and the positions of the nodes from their
+// pos attributes. Subgraphs are read as part of the graph, and edges to subgraphs aren't
+// supported.
+fn import_dot(contents: &str) -> Result<ImportedGraph, String> {
+    let tokens = dot_tokens(contents)?;
+    let mut i = 0;
+    let keyword = |expected: &[&str], i: &mut usize| match tokens.get(*i) {
+        Some(DotToken::Id(id)) if expected.contains(&id.to_lowercase().as_str()) => {
+            *i += 1;
+            true
+        }
+        _ => false,
+    };
+    keyword(&["strict"], &mut i);
+    if !keyword(&["graph", "digraph"], &mut i) {
+        return Err("The DOT file doesn't start with graph or digraph.".to_string());
+    }
+    let name = match tokens.get(i) {
+        Some(DotToken::Id(name)) => {
+            i += 1;
+            Some(name.clone())
+        }
+        _ => None,
+    };
+    if tokens.get(i) != Some(&DotToken::Symbol('{')) {
+        return Err("The DOT graph has no body.".to_string());
+    }
+    i += 1;
+
+    let mut vertex_indices = VertexIndices::default();
+    let mut edges = vec![];
+    let mut positions: HashMap<usize, (f32, f32)> = HashMap::new();
+    let mut depth = 1;
+    while depth > 0 {
+        match tokens.get(i) {
+            None => return Err("The DOT graph has no closing brace.".to_string()),
+            Some(DotToken::Symbol('{')) => {
+                depth += 1;
+                i += 1;
+            }
+            Some(DotToken::Symbol('}')) => {
+                depth -= 1;
+                i += 1;
+            }
+            Some(DotToken::Symbol(';' | ',')) => i += 1,
+            Some(DotToken::Id(id))
+                if ["graph", "node", "edge"].contains(&id.to_lowercase().as_str())
+                    && tokens.get(i + 1) == Some(&DotToken::Symbol('[')) =>
+            {
+                i += 1;
+                dot_attributes(&tokens, &mut i)?;
+            }
+            Some(DotToken::Id(id)) if id.to_lowercase() == "subgraph" => {
+                i += 1;
+                if let Some(DotToken::Id(_)) = tokens.get(i) {
+                    i += 1;
+                }
+            }
+            Some(DotToken::Id(_)) if tokens.get(i + 1) == Some(&DotToken::Symbol('=')) => {
+                i += 3;
+            }
+            Some(DotToken::Id(_)) => {
+                let mut nodes = vec![vertex_indices.index(&dot_node(&tokens, &mut i)?)];
+                while tokens.get(i) == Some(&DotToken::Edge) {
+                    i += 1;
+                    nodes.push(vertex_indices.index(&dot_node(&tokens, &mut i)?));
+                }
+                let attributes = dot_attributes(&tokens, &mut i)?;
+                if nodes.len() == 1 {
+                    if let Some(position) = attributes.get("pos").and_then(|pos| parse_pos(pos)) {
+                        positions.insert(nodes[0], position);
+                    }
+                }
+                edges.extend(nodes.windows(2).map(|pair| (pair[0], pair[1])));
+            }
+            Some(token) => return Err(format!("The DOT file has an unexpected {token:?}.")),
+        }
+    }
+
+    Ok(ImportedGraph {
+        number_of_vertices: vertex_indices.len(),
+        edges,
+        // DOT's y axis points up, ours points down.
+        positions: (0..vertex_indices.len())
+            .map(|v| positions.get(&v).map(|&(x, y)| (x, -y)))
+            .collect(),
+        name,
+    })
+}
+
+// A DOT position "x,y", optionally followed by "!" if it's fixed.
+fn parse_pos(pos: &str) -> Option<(f32, f32)> {
+    let (x, y) = pos.trim_end_matches('!').split_once(',')?;
+    let y = y.split(',').next()?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+// GraphML
+
+fn escape_xml(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn unescape_xml(string: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = string;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| {
+                    entity
+                        .strip_prefix('#')
+                        .and_then(|decimal| decimal.parse().ok())
+                })
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                unescaped.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+// The positions are stored as the x and y attributes of the nodes, like networkx
+// and Gephi do.
+fn export_graphml(graph: &Graph) -> String {
+    let mut graphml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+         <key id=\"x\" for=\"node\" attr.name=\"x\" attr.type=\"double\"/>\n  \
+         <key id=\"y\" for=\"node\" attr.name=\"y\" attr.type=\"double\"/>\n",
+    );
+    graphml.push_str(&format!(
+        "  <graph id=\"{}\" edgedefault=\"undirected\">\n",
+        escape_xml(&graph.name)
+    ));
+    for (v, &(x, y)) in graph.vertices.iter().enumerate() {
+        graphml.push_str(&format!(
+            "    <node id=\"n{v}\"><data key=\"x\">{x}</data><data key=\"y\">{y}</data></node>\n"
+        ));
+    }
+    for (v, u) in edges(graph) {
+        graphml.push_str(&format!("    <edge source=\"n{v}\" target=\"n{u}\"/>\n"));
+    }
+    graphml.push_str("  </graph>\n</graphml>\n");
+    graphml
+}
+
+enum XmlEvent {
+    Start {
+        name: String,
+        attributes: HashMap<String, String>,
+        // Whether the element is empty, e.g. <edge/>, so there is no end tag.
+        empty: bool,
+    },
+    End {
+        name: String,
+    },
+    Text(String),
+}
+
+// Splits XML into tags and text, skipping comments, processing instructions and declarations.
+// Names are without their namespace prefix.
+fn xml_events(contents: &str) -> Result<Vec<XmlEvent>, String> {
+    let local_name = |name: &str| name.rsplit(':').next().unwrap_or(name).to_string();
+    let mut events = vec![];
+    let mut rest = contents;
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            events.push(XmlEvent::Text(unescape_xml(&rest[..start])));
+        }
+        rest = &rest[start..];
+        let skip_to = |rest: &str, end: &str| {
+            rest.find(end)
+                .map(|index| index + end.len())
+                .ok_or_else(|| "The GraphML file has an unterminated tag.".to_string())
+        };
+        if rest.starts_with("<!--") {
+            rest = &rest[skip_to(rest, "-->")?..];
+        } else if rest.starts_with("<?") {
+            rest = &rest[skip_to(rest, "?>")?..];
+        } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = skip_to(cdata, "]]>")?;
+            events.push(XmlEvent::Text(cdata[..end - 3].to_string()));
+            rest = &cdata[end..];
+        } else if rest.starts_with("<!") {
+            rest = &rest[skip_to(rest, ">")?..];
+        } else if let Some(end_tag) = rest.strip_prefix("</") {
+            let end = skip_to(end_tag, ">")?;
+            events.push(XmlEvent::End {
+                name: local_name(end_tag[..end - 1].trim()),
+            });
+            rest = &end_tag[end..];
+        } else {
+            // We find the end of the tag, skipping '>' in attribute values.
+            let mut quote = None;
+            let end = rest
+                .char_indices()
+                .find(|&(_, c)| {
+                    match quote {
+                        Some(q) if c == q => quote = None,
+                        None if c == '"' || c == '\'' => quote = Some(c),
+                        _ => {}
+                    }
+                    quote.is_none() && c == '>'
+                })
+                .map(|(index, _)| index)
+                .ok_or_else(|| "The GraphML file has an unterminated tag.".to_string())?;
+            let tag = &rest[1..end];
+            let empty = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, mut attributes_text) =
+                tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let mut attributes = HashMap::new();
+            while let Some((key, value)) = attributes_text.split_once('=') {
+                let value = value.trim_start();
+                let Some(quote) = value.chars().next().filter(|&c| c == '"' || c == '\'') else {
+                    return Err("The GraphML file has an unquoted attribute.".to_string());
+                };
+                let Some(value_end) = value[1..].find(quote) else {
+                    return Err("The GraphML file has an unterminated attribute.".to_string());
+                };
+                attributes.insert(
+                    local_name(key.trim()),
+                    unescape_xml(&value[1..value_end + 1]),
+                );
+                attributes_text = &value[value_end + 2..];
+            }
+            events.push(XmlEvent::Start {
+                name: local_name(name),
+                attributes,
+                empty,
+            });
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(events)
+}
+
+// Reads the nodes and edges of a GraphML graph, and the positions of the nodes from their
+// x and y attributes. Nested graphs are read as part of the graph.
+fn import_graphml(contents: &str) -> Result<ImportedGraph, String> {
+    let events = xml_events(contents)?;
+
+    // The attribute names of the node keys.
+    let mut key_names = HashMap::new();
+    let mut name = None;
+    let mut vertex_indices = VertexIndices::default();
+    let mut edges = vec![];
+    let mut positions: HashMap<usize, (Option<f32>, Option<f32>)> = HashMap::new();
+    // The node whose element we're in, and the key of the data element we're in.
+    let mut current_node = None;
+    let mut current_key: Option<String> = None;
+    for event in events {
+        match event {
+            XmlEvent::Start {
+                name: element,
+                attributes,
+                empty,
+            } => match element.as_str() {
+                "key" => {
+                    if let (Some(id), Some(attribute_name)) =
+                        (attributes.get("id"), attributes.get("attr.name"))
+                    {
+                        key_names.insert(id.clone(), attribute_name.clone());
+                    }
+                }
+                "graph" if name.is_none() => name = attributes.get("id").cloned(),
+                "node" => {
+                    let id = attributes
+                        .get("id")
+                        .ok_or_else(|| "The GraphML file has a node without an id.".to_string())?;
+                    let index = vertex_indices.index(id);
+                    current_node = (!empty).then_some(index);
+                }
+                "edge" => {
+                    let (Some(source), Some(target)) =
+                        (attributes.get("source"), attributes.get("target"))
+                    else {
+                        return Err("The GraphML file has an edge without its nodes.".to_string());
+                    };
+                    edges.push((source.clone(), target.clone()));
+                }
+                "data" if !empty => current_key = attributes.get("key").cloned(),
+                _ => {}
+            },
+            XmlEvent::End { name: element } => match element.as_str() {
+                "node" => current_node = None,
+                "data" => current_key = None,
+                _ => {}
+            },
+            XmlEvent::Text(text) => {
+                let (Some(node), Some(key)) = (current_node, &current_key) else {
+                    continue;
+                };
+                let Ok(value) = text.trim().parse::<f32>() else {
+                    continue;
+                };
+                let position = positions.entry(node).or_default();
+                match key_names.get(key).map(String::as_str) {
+                    Some("x") => position.0 = Some(value),
+                    Some("y") => position.1 = Some(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let edges = edges
+        .iter()
+        .map(|(source, target)| (vertex_indices.index(source), vertex_indices.index(target)))
+        .collect();
+    Ok(ImportedGraph {
+        number_of_vertices: vertex_indices.len(),
+        edges,
+        positions: (0..vertex_indices.len())
+            .map(|v| match positions.get(&v) {
+                Some(&(Some(x), Some(y))) => Some((x, y)),
+                _ => None,
+            })
+            .collect(),
+        name,
+    })
+}
+
+// graph6 and sparse6, see https://users.cecs.anu.edu.au/~bdm/data/formats.txt
+
+// The number of vertices, in the big-endian 6 bit groups of the formats.
+fn encode_graph6_size(number_of_vertices: usize) -> Vec<u8> {
+    let groups = |number_of_groups: usize| {
+        (0..number_of_groups)
+            .rev()
+            .map(move |group| ((number_of_vertices >> (6 * group)) & 63) as u8 + 63)
+    };
+    if number_of_vertices < 63 {
+        vec![number_of_vertices as u8 + 63]
+    } else if number_of_vertices < 1 << 18 {
+        std::iter::once(126).chain(groups(3)).collect()
+    } else {
+        [126, 126].into_iter().chain(groups(6)).collect()
+    }
+}
+
+// Packs the bits into characters of 6 bits, padding the last one with pad.
+fn encode_graph6_bits(bits: &[bool], pad: bool) -> impl Iterator<Item = u8> + '_ {
+    bits.chunks(6).map(move |chunk| {
+        (0..6).fold(0, |byte, i| {
+            (byte << 1) | u8::from(chunk.get(i).copied().unwrap_or(pad))
+        }) + 63
+    })
+}
+
+fn export_graph6(graph: &Graph) -> String {
+    let number_of_vertices = graph.vertices.len();
+    let mut bits = vec![];
+    for u in 0..number_of_vertices {
+        for v in 0..u {
+            bits.push(graph.adjacency_list[v].contains(&u));
+        }
+    }
+    let bytes: Vec<u8> = encode_graph6_size(number_of_vertices)
+        .into_iter()
+        .chain(encode_graph6_bits(&bits, false))
+        .collect();
+    // The bytes are all printable ASCII.
+    String::from_utf8(bytes).unwrap() + "\n"
+}
+
+// The number of bits of a vertex in sparse6.
+fn sparse6_vertex_bits(number_of_vertices: usize) -> usize {
+    let mut k = 1;
+    while 1 << k < number_of_vertices {
+        k += 1;
+    }
+    k
+}
+
+fn export_sparse6(graph: &Graph) -> String {
+    let number_of_vertices = graph.vertices.len();
+    let k = sparse6_vertex_bits(number_of_vertices);
+    let mut bits = vec![];
+    let push_vertex = |bits: &mut Vec<bool>, vertex: usize| {
+        bits.extend((0..k).rev().map(|bit| vertex & (1 << bit) != 0));
+    };
+
+    // The edges are sorted by their larger vertex, which is the current vertex of the format.
+    let mut sorted_edges: Vec<_> = edges(graph).map(|(v, u)| (u, v)).collect();
+    sorted_edges.sort_unstable();
+    let mut current_vertex = 0;
+    for (u, v) in sorted_edges {
+        if u == current_vertex {
+            bits.push(false);
+            push_vertex(&mut bits, v);
+        } else if u == current_vertex + 1 {
+            current_vertex += 1;
+            bits.push(true);
+            push_vertex(&mut bits, v);
+        } else {
+            current_vertex = u;
+            bits.push(true);
+            push_vertex(&mut bits, u);
+            bits.push(false);
+            push_vertex(&mut bits, v);
+        }
+    }
+    // Padding with ones could be read as an edge from the last vertex in this case.
+    let padding = (6 - bits.len() % 6) % 6;
+    if k < 6
+        && number_of_vertices == 1 << k
+        && padding >= k
+        && current_vertex + 1 < number_of_vertices
+    {
+        bits.push(false);
+    }
+
+    let bytes: Vec<u8> = std::iter::once(b':')
+        .chain(encode_graph6_size(number_of_vertices))
+        .chain(encode_graph6_bits(&bits, true))
+        .collect();
+    String::from_utf8(bytes).unwrap() + "\n"
+}
+
+// Reads the first graph of a graph6 or sparse6 file, telling them apart by the ':' of sparse6.
+fn import_graph6(contents: &str) -> Result<ImportedGraph, String> {
+    let invalid = || "The graph6/sparse6 file is invalid.".to_string();
+    let line = contents
+        .trim_start()
+        .trim_start_matches(">>graph6<<")
+        .trim_start_matches(">>sparse6<<")
+        .lines()
+        .next()
+        .ok_or_else(invalid)?
+        .trim();
+    let (sparse, line) = match line.strip_prefix(':') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    let groups = line
+        .bytes()
+        .map(|byte| {
+            (63..=126)
+                .contains(&byte)
+                .then_some((byte - 63) as usize)
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // The value of the big-endian groups of 6 bits from start to end.
+    let value = |start: usize, end: usize| {
+        groups
+            .get(start..end)
+            .map(|groups| groups.iter().fold(0, |value, &group| (value << 6) | group))
+            .ok_or_else(invalid)
+    };
+    let (number_of_vertices, size_length) = match (groups.first(), groups.get(1)) {
+        (None, _) => return Err(invalid()),
+        (Some(&first), _) if first < 63 => (first, 1),
+        (_, Some(&second)) if second < 63 => (value(1, 4)?, 4),
+        _ => (value(2, 8)?, 8),
+    };
+    check_number_of_vertices(number_of_vertices)?;
+    let bits: Vec<bool> = groups[size_length..]
+        .iter()
+        .flat_map(|&group| (0..6).rev().map(move |bit| group & (1 << bit) != 0))
+        .collect();
+
+    let mut edges = vec![];
+    if sparse {
+        let k = sparse6_vertex_bits(number_of_vertices);
+        let mut bits = bits.chunks_exact(k + 1);
+        let mut v = 0;
+        for chunk in &mut bits {
+            let x = chunk[1..]
+                .iter()
+                .fold(0, |x, &bit| (x << 1) | usize::from(bit));
+            if chunk[0] {
+                v += 1;
+            }
+            if x >= number_of_vertices || v >= number_of_vertices {
+                break;
+            } else if x > v {
+                v = x;
+            } else {
+                edges.push((x, v));
+            }
+        }
+    } else {
+        let mut bits = bits.into_iter();
+        for u in 0..number_of_vertices {
+            for v in 0..u {
+                if bits.next().ok_or_else(invalid)? {
+                    edges.push((v, u));
+                }
+            }
+        }
+    }
+
+    Ok(ImportedGraph {
+        number_of_vertices,
+        edges,
+        positions: vec![],
+        name: None,
+    })
+}
+
+// Edge lists
+
+// Isolated vertices are written on their own line.
+fn export_edge_list(graph: &Graph) -> String {
+    let mut edge_list = String::new();
+    for (v, neighbours) in graph.adjacency_list.iter().enumerate() {
+        if neighbours.is_empty() {
+            edge_list.push_str(&format!("{v}\n"));
+        }
+    }
+    for (v, u) in edges(graph) {
+        edge_list.push_str(&format!("{v} {u}\n"));
+    }
+    edge_list
+}
+
+// Reads the first two words of every line as an edge, and a single word as a vertex, like
+// networkx's edge lists (whose edge data we ignore). If every vertex is a number,
+// the numbers are the indices of the vertices, otherwise they're indexed in order.
+fn import_edge_list(contents: &str) -> Result<ImportedGraph, String> {
+    let lines: Vec<Vec<&str>> = contents
+        .lines()
+        .map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            line.split_whitespace().take(2).collect::<Vec<_>>()
+        })
+        .filter(|words| !words.is_empty())
+        .collect();
+
+    let numbers: Option<Vec<Vec<usize>>> = lines
+        .iter()
+        .map(|words| words.iter().map(|word| word.parse().ok()).collect())
+        .collect();
+    let (number_of_vertices, lines) = match numbers {
+        Some(numbers) => {
+            let max = numbers.iter().flatten().max().copied();
+            // We check the largest vertex before adding 1, so it can't overflow.
+            if let Some(max) = max.filter(|&max| max >= MAX_IMPORTED_VERTICES) {
+                return Err(format!(
+                    "The graph has the vertex {max}, but we can only import {MAX_IMPORTED_VERTICES} vertices."
+                ));
+            }
+            (max.map_or(0, |max| max + 1), numbers)
+        }
+        None => {
+            let mut vertex_indices = VertexIndices::default();
+            let lines = lines
+                .iter()
+                .map(|words| {
+                    words
+                        .iter()
+                        .map(|word| vertex_indices.index(word))
+                        .collect()
+                })
+                .collect();
+            (vertex_indices.len(), lines)
+        }
+    };
+    check_number_of_vertices(number_of_vertices)?;
+
+    Ok(ImportedGraph {
+        number_of_vertices,
+        edges: lines
+            .iter()
+            .filter(|vertices| vertices.len() == 2)
+            .map(|vertices| (vertices[0], vertices[1]))
+            .collect(),
+        positions: vec![],
+        name: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game::template_graphs, generators::GraphGenerator};
+
+    // The edges of the graph, sorted, to compare graphs whatever the order of their neighbours.
+    fn sorted_edges(graph: &Graph) -> Vec<(usize, usize)> {
+        let mut edges: Vec<_> = edges(graph).collect();
+        edges.sort_unstable();
+        edges
+    }
+
+    #[test]
+    fn every_format_round_trips() {
+        let mut graphs = template_graphs();
+        graphs.push(GraphGenerator::Petersen.generate().unwrap());
+        graphs.push(
+            GraphGenerator::Grid {
+                rows: 3,
+                columns: 4,
+            }
+            .generate()
+            .unwrap(),
+        );
+        for graph in &graphs {
+            for format in GraphFormat::ALL {
+                let contents = export_graph(graph, format).unwrap();
+                let imported = import_graph(&contents, format, "Imported").unwrap();
+                let message = format!("{} as {}", graph.name, format.name());
+                assert_eq!(imported.vertices.len(), graph.vertices.len(), "{message}");
+                assert_eq!(sorted_edges(&imported), sorted_edges(graph), "{message}");
+                if matches!(
+                    format,
+                    GraphFormat::Json | GraphFormat::Dot | GraphFormat::GraphMl
+                ) {
+                    assert_eq!(imported.name, graph.name, "{message}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn positions_are_kept() {
+        let graph = GraphGenerator::Petersen.generate().unwrap();
+        for format in [GraphFormat::Json, GraphFormat::Dot, GraphFormat::GraphMl] {
+            let contents = export_graph(&graph, format).unwrap();
+            let imported = import_graph(&contents, format, "Imported").unwrap();
+            for (&(x, y), &(imported_x, imported_y)) in
+                graph.vertices.iter().zip(&imported.vertices)
+            {
+                assert!((x - imported_x).abs() < 1e-3, "{}", format.name());
+                assert!((y - imported_y).abs() < 1e-3, "{}", format.name());
+            }
+        }
+    }
+
+    #[test]
+    fn reads_networkx_samples() {
+        // The Petersen graph from networkx's documentation.
+        let petersen = import_graph("IheA@GUAo", GraphFormat::Graph6, "Petersen").unwrap();
+        assert_eq!(petersen.vertices.len(), 10);
+        assert!(petersen
+            .adjacency_list
+            .iter()
+            .all(|neighbours| neighbours.len() == 3));
+
+        let sparse = import_graph(":Fa@x^", GraphFormat::Sparse6, "Sparse").unwrap();
+        assert_eq!(sparse.vertices.len(), 7);
+        assert_eq!(sorted_edges(&sparse), vec![(0, 1), (0, 2), (1, 2), (5, 6)]);
+
+        let edge_list = import_graph(
+            "a b\nb c # a comment\nd\n",
+            GraphFormat::EdgeList,
+            "Letters",
+        )
+        .unwrap();
+        assert_eq!(edge_list.name, "Letters");
+        assert_eq!(edge_list.vertices.len(), 4);
+        assert_eq!(sorted_edges(&edge_list), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn rejects_malformed_files() {
+        for (contents, format) in [
+            ("graph { a -- b [label=<", GraphFormat::Dot),
+            ("graph { a -- b [label=\"", GraphFormat::Dot),
+            ("0 18446744073709551615", GraphFormat::EdgeList),
+            ("0 1000", GraphFormat::EdgeList),
+            ("", GraphFormat::EdgeList),
+            (
+                "{\"name\": \"Missing\", \"vertices\": [[0, 0]], \"adjacency_list\": [[1]]}",
+                GraphFormat::Json,
+            ),
+            ("<graphml><graph>", GraphFormat::GraphMl),
+        ] {
+            assert!(import_graph(contents, format, "Bad").is_err(), "{contents}");
+        }
+    }
+
+    #[test]
+    fn json_graphs_are_made_simple_and_undirected() {
+        let contents = r#"{
+            "name": "Directed",
+            "vertices": [[0.1, 0.1], [0.5, 0.5], [0.9, 0.9]],
+            "adjacency_list": [[1, 1, 0], [], [0]]
+        }"#;
+        let graph = import_graph(contents, GraphFormat::Json, "Imported").unwrap();
+        assert_eq!(graph.adjacency_list, vec![vec![1, 2], vec![0], vec![0]]);
+        // The coordinates aren't normalised.
+        assert_eq!(graph.vertices[1], (0.5, 0.5));
+
+        let too_large = Graph {
+            name: "Too large".to_string(),
+            vertices: vec![(0.5, 0.5); MAX_IMPORTED_VERTICES + 1],
+            adjacency_list: vec![vec![]; MAX_IMPORTED_VERTICES + 1],
+        };
+        let contents = export_graph(&too_large, GraphFormat::Json).unwrap();
+        assert!(import_graph(&contents, GraphFormat::Json, "Imported").is_err());
+    }
+}
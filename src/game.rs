@@ -1,11 +1,23 @@
-use rand::{
-    distributions::{Uniform, WeightedIndex},
-    prelude::Distribution,
-    Rng,
+use rand::{distributions::Uniform, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, VecDeque},
 };
-use std::collections::HashMap;
 
-#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
+use crate::{
+    app::{
+        show_evasive_settings, show_mcts_settings, show_menace_settings, show_qlearning_settings,
+    },
+    heuristics::{ChaserCop, EvasiveRobber},
+    human::{HumanCop, HumanRobber},
+    mcts::{MctsCop, MctsRobber},
+    optimal::{check_solvable, OptimalCop, OptimalRobber},
+    qlearning::{QLearningCop, QLearningRobber},
+    symmetry::Symmetries,
+};
+
+#[derive(Default, Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Graph {
     pub name: String,
     pub vertices: Vec<(f32, f32)>,
@@ -46,30 +58,434 @@ pub fn template_graphs() -> Vec<Graph> {
     ]
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, serde::Deserialize, serde::Serialize)]
-pub enum Algorithm {
-    Random,
-    Menace,
+pub type CopPositions = Vec<usize>;
+pub type RobberPosition = usize;
+
+/// The random number generator shared by both players of a game.
+/// Every random choice is made with it, so a game is reproducible from its seed.
+pub type GameRng = StdRng;
+
+/// All the positions the cops can move to in one step. Each cop can stay at its
+/// current position or move to one of its neighbours.
+pub fn possible_cop_moves(graph: &Graph, cop_positions: &CopPositions) -> Vec<CopPositions> {
+    let mut moves = vec![vec![]];
+    for &cop_position in cop_positions {
+        let neighbours = &graph.adjacency_list[cop_position];
+        moves = moves
+            .into_iter()
+            .flat_map(|position: CopPositions| {
+                neighbours.iter().chain(std::iter::once(&cop_position)).map(
+                    move |&new_cop_position| {
+                        let mut position = position.clone();
+                        position.push(new_cop_position);
+                        position
+                    },
+                )
+            })
+            .collect();
+    }
+    moves
+}
+
+/// A cop algorithm. Implement this to add a new cop to the game
+/// and register it with a [`StrategyRegistry`].
+pub trait CopStrategy: Send + Sync {
+    /// Chooses the starting positions of the cops.
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions;
+
+    /// Chooses the next positions of the cops. Each cop can stay at its
+    /// current position or move to one of its neighbours.
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions;
+
+    /// Called with the final positions once a match is over.
+    fn end(&mut self, graph: &Graph, cop_positions: &CopPositions, robber_position: RobberPosition);
+
+    /// Called before every move with the number of robber moves left in the match
+    /// (like [`Game::steps_left`]), for strategies which look ahead.
+    fn set_steps_left(&mut self, _steps_left: u8) {}
+
+    /// Whether choosing the next move can take long, e.g. because the strategy searches or
+    /// solves the game first. The app chooses such moves without blocking the game view.
+    fn is_slow(&self) -> bool {
+        false
+    }
+
+    /// Used by the app to downcast to a concrete strategy when showing its statistics.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Used by the app to downcast to a concrete strategy when changing it, e.g. loading a MENACE brain.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// A robber algorithm. Implement this to add a new robber to the game
+/// and register it with a [`StrategyRegistry`].
+pub trait RobberStrategy: Send + Sync {
+    /// Chooses the starting position of the robber, after the cops have chosen theirs.
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition;
+
+    /// Chooses the next position of the robber. The robber can stay at its
+    /// current position or move to one of its neighbours.
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition;
+
+    /// Called with the final positions once a match is over.
+    fn end(&mut self, graph: &Graph, cop_positions: &CopPositions, robber_position: RobberPosition);
+
+    /// Called before every move with the number of robber moves left in the match, including
+    /// the move being chosen (like [`Game::steps_left`] before the move), for strategies which
+    /// look ahead.
+    fn set_steps_left(&mut self, _steps_left: u8) {}
+
+    /// Whether choosing the next move can take long, see [`CopStrategy::is_slow`].
+    fn is_slow(&self) -> bool {
+        false
+    }
+
+    /// Used by the app to downcast to a concrete strategy when showing its statistics.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Used by the app to downcast to a concrete strategy when changing it, e.g. loading a MENACE brain.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// The parameters of the strategies of one side, chosen in the game settings, by the
+/// registry name of their strategy. Every strategy has its own type of settings, which we
+/// keep as JSON, so the settings of any registered strategy are saved with the app and
+/// exported with the statistics.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub struct StrategySettings(BTreeMap<String, serde_json::Value>);
+
+impl StrategySettings {
+    /// The settings of a strategy, or their default if they haven't been set (or were saved
+    /// with another type).
+    pub fn get<T: Default + serde::de::DeserializeOwned>(&self, name: &str) -> T {
+        self.0
+            .get(name)
+            .and_then(|value| T::deserialize(value).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set<T: serde::Serialize>(&mut self, name: &str, settings: &T) {
+        // Settings are plain data, so they always serialize.
+        let value = serde_json::to_value(settings).expect("settings should serialize to JSON");
+        self.0.insert(name.to_string(), value);
+    }
+
+    /// Changes the settings of a strategy with edit, which is given their current value.
+    /// They're only stored if they changed, as settings UIs edit them every frame.
+    pub fn edit<T>(&mut self, name: &str, edit: impl FnOnce(&mut T))
+    where
+        T: Default + PartialEq + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let old_settings: T = self.get(name);
+        let mut settings: T = self.get(name);
+        edit(&mut settings);
+        if settings != old_settings {
+            self.set(name, &settings);
+        }
+    }
+}
+
+/// Creates a cop strategy for the given number of cops.
+pub type CopConstructor =
+    fn(number_of_cops: u8, settings: &StrategySettings) -> Box<dyn CopStrategy>;
+/// Creates a robber strategy for the given number of cops.
+pub type RobberConstructor =
+    fn(number_of_cops: u8, settings: &StrategySettings) -> Box<dyn RobberStrategy>;
+
+/// Checks whether a strategy can play on a graph with a number of cops, for example whether
+/// its tables fit in memory, and returns why not otherwise.
+pub type GraphRequirement = fn(graph: &Graph, number_of_cops: u8) -> Result<(), String>;
+
+/// Shows the settings of a strategy in the game settings, below the choice of the strategy,
+/// and lets the user change them.
+pub type SettingsUi = fn(ui: &mut egui::Ui, settings: &mut StrategySettings);
+
+/// The named cop and robber strategies which can be selected in the game settings.
+/// The default registry contains the built in strategies.
+#[derive(Clone)]
+pub struct StrategyRegistry {
+    cops: Vec<(String, CopConstructor)>,
+    robbers: Vec<(String, RobberConstructor)>,
+    cop_requirements: Vec<(String, GraphRequirement)>,
+    robber_requirements: Vec<(String, GraphRequirement)>,
+    cop_settings_uis: Vec<(String, SettingsUi)>,
+    robber_settings_uis: Vec<(String, SettingsUi)>,
+}
+
+impl StrategyRegistry {
+    /// A registry without any strategies.
+    pub fn empty() -> Self {
+        Self {
+            cops: Vec::new(),
+            robbers: Vec::new(),
+            cop_requirements: Vec::new(),
+            robber_requirements: Vec::new(),
+            cop_settings_uis: Vec::new(),
+            robber_settings_uis: Vec::new(),
+        }
+    }
+
+    /// Adds a cop strategy. A strategy registered with an existing name replaces the old one,
+    /// together with its requirement and settings UI.
+    pub fn register_cop(&mut self, name: &str, constructor: CopConstructor) {
+        self.cop_requirements.retain(|(n, _)| n != name);
+        self.cop_settings_uis.retain(|(n, _)| n != name);
+        if let Some(entry) = self.cops.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = constructor;
+        } else {
+            self.cops.push((name.to_string(), constructor));
+        }
+    }
+
+    /// Adds a robber strategy. A strategy registered with an existing name replaces the old one,
+    /// together with its requirement and settings UI.
+    pub fn register_robber(&mut self, name: &str, constructor: RobberConstructor) {
+        self.robber_requirements.retain(|(n, _)| n != name);
+        self.robber_settings_uis.retain(|(n, _)| n != name);
+        if let Some(entry) = self.robbers.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = constructor;
+        } else {
+            self.robbers.push((name.to_string(), constructor));
+        }
+    }
+
+    /// Restricts the graphs and numbers of cops a registered cop strategy can play with.
+    pub fn require_cop(&mut self, name: &str, requirement: GraphRequirement) {
+        self.cop_requirements.push((name.to_string(), requirement));
+    }
+
+    /// Restricts the graphs and numbers of cops a registered robber strategy can play with.
+    pub fn require_robber(&mut self, name: &str, requirement: GraphRequirement) {
+        self.robber_requirements
+            .push((name.to_string(), requirement));
+    }
+
+    /// Shows the settings of a registered cop strategy in the game settings, replacing its
+    /// previous settings UI.
+    pub fn set_cop_settings_ui(&mut self, name: &str, settings_ui: SettingsUi) {
+        self.cop_settings_uis.retain(|(n, _)| n != name);
+        self.cop_settings_uis.push((name.to_string(), settings_ui));
+    }
+
+    /// Shows the settings of a registered robber strategy in the game settings, replacing its
+    /// previous settings UI.
+    pub fn set_robber_settings_ui(&mut self, name: &str, settings_ui: SettingsUi) {
+        self.robber_settings_uis.retain(|(n, _)| n != name);
+        self.robber_settings_uis
+            .push((name.to_string(), settings_ui));
+    }
+
+    /// Shows the settings UI of a cop strategy, if it has one.
+    pub fn show_cop_settings(
+        &self,
+        name: &str,
+        ui: &mut egui::Ui,
+        settings: &mut StrategySettings,
+    ) {
+        if let Some((_, settings_ui)) = self.cop_settings_uis.iter().find(|(n, _)| n == name) {
+            settings_ui(ui, settings);
+        }
+    }
+
+    /// Shows the settings UI of a robber strategy, if it has one.
+    pub fn show_robber_settings(
+        &self,
+        name: &str,
+        ui: &mut egui::Ui,
+        settings: &mut StrategySettings,
+    ) {
+        if let Some((_, settings_ui)) = self.robber_settings_uis.iter().find(|(n, _)| n == name) {
+            settings_ui(ui, settings);
+        }
+    }
+
+    /// Checks that a cop strategy is registered and can play on the graph with the number of cops.
+    pub fn check_cop(&self, name: &str, graph: &Graph, number_of_cops: u8) -> Result<(), String> {
+        if !self.cop_names().any(|n| n == name) {
+            return Err(format!("Unknown cop algorithm {name}"));
+        }
+        self.cop_requirements
+            .iter()
+            .filter(|(n, _)| n == name)
+            .try_for_each(|(_, requirement)| requirement(graph, number_of_cops))
+    }
+
+    /// Checks that a robber strategy is registered and can play on the graph with the number
+    /// of cops.
+    pub fn check_robber(
+        &self,
+        name: &str,
+        graph: &Graph,
+        number_of_cops: u8,
+    ) -> Result<(), String> {
+        if !self.robber_names().any(|n| n == name) {
+            return Err(format!("Unknown robber algorithm {name}"));
+        }
+        self.robber_requirements
+            .iter()
+            .filter(|(n, _)| n == name)
+            .try_for_each(|(_, requirement)| requirement(graph, number_of_cops))
+    }
+
+    pub fn cop_names(&self) -> impl Iterator<Item = &str> {
+        self.cops.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn robber_names(&self) -> impl Iterator<Item = &str> {
+        self.robbers.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn create_cop(
+        &self,
+        name: &str,
+        number_of_cops: u8,
+        settings: &StrategySettings,
+    ) -> Option<Box<dyn CopStrategy>> {
+        self.cops
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, constructor)| constructor(number_of_cops, settings))
+    }
+
+    pub fn create_robber(
+        &self,
+        name: &str,
+        number_of_cops: u8,
+        settings: &StrategySettings,
+    ) -> Option<Box<dyn RobberStrategy>> {
+        self.robbers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, constructor)| constructor(number_of_cops, settings))
+    }
 }
 
-type CopPositions = Vec<usize>;
-type RobberPosition = usize;
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register_cop("Random", |number_of_cops, _| {
+            Box::new(RandomCop::new(number_of_cops))
+        });
+        registry.register_cop("Menace", |number_of_cops, settings| {
+            Box::new(MenaceCop::new(number_of_cops, settings.get("Menace")))
+        });
+        registry.register_cop("Q-learning", |number_of_cops, settings| {
+            Box::new(QLearningCop::new(
+                number_of_cops,
+                settings.get("Q-learning"),
+            ))
+        });
+        registry.register_cop("MCTS", |number_of_cops, settings| {
+            Box::new(MctsCop::new(number_of_cops, settings.get("MCTS")))
+        });
+        registry.require_cop("Menace", check_state_encoding);
+        registry.require_cop("Q-learning", check_state_encoding);
+        registry.require_cop("MCTS", check_cop_move_encoding);
+        registry.set_cop_settings_ui("Menace", |ui, settings| {
+            settings.edit("Menace", |menace| {
+                show_menace_settings(ui, "Cop MENACE settings", menace)
+            })
+        });
+        registry.set_cop_settings_ui("Q-learning", |ui, settings| {
+            settings.edit("Q-learning", |qlearning| {
+                show_qlearning_settings(ui, "Cop Q-learning settings", qlearning)
+            })
+        });
+        registry.set_cop_settings_ui("MCTS", |ui, settings| {
+            settings.edit("MCTS", |mcts| {
+                show_mcts_settings(ui, "Cop MCTS settings", mcts)
+            })
+        });
+        registry.register_cop("Optimal", |number_of_cops, _| {
+            Box::new(OptimalCop::new(number_of_cops))
+        });
+        registry.require_cop("Optimal", check_solvable);
+        registry.register_cop("Chaser", |number_of_cops, _| {
+            Box::new(ChaserCop::new(number_of_cops))
+        });
+        registry.register_cop("Human", |number_of_cops, _| {
+            Box::new(HumanCop::new(number_of_cops))
+        });
+        registry.register_robber("Random", |_, _| Box::new(RandomRobber::new()));
+        registry.register_robber("Menace", |number_of_cops, settings| {
+            Box::new(MenaceRobber::new(number_of_cops, settings.get("Menace")))
+        });
+        registry.register_robber("Q-learning", |number_of_cops, settings| {
+            Box::new(QLearningRobber::new(
+                number_of_cops,
+                settings.get("Q-learning"),
+            ))
+        });
+        registry.register_robber("MCTS", |number_of_cops, settings| {
+            Box::new(MctsRobber::new(number_of_cops, settings.get("MCTS")))
+        });
+        registry.require_robber("Menace", check_state_encoding);
+        registry.require_robber("Q-learning", check_state_encoding);
+        registry.require_robber("MCTS", check_cop_move_encoding);
+        registry.set_robber_settings_ui("Menace", |ui, settings| {
+            settings.edit("Menace", |menace| {
+                show_menace_settings(ui, "Robber MENACE settings", menace)
+            })
+        });
+        registry.set_robber_settings_ui("Q-learning", |ui, settings| {
+            settings.edit("Q-learning", |qlearning| {
+                show_qlearning_settings(ui, "Robber Q-learning settings", qlearning)
+            })
+        });
+        registry.set_robber_settings_ui("MCTS", |ui, settings| {
+            settings.edit("MCTS", |mcts| {
+                show_mcts_settings(ui, "Robber MCTS settings", mcts)
+            })
+        });
+        registry.register_robber("Optimal", |number_of_cops, _| {
+            Box::new(OptimalRobber::new(number_of_cops))
+        });
+        registry.require_robber("Optimal", check_solvable);
+        registry.register_robber("Evasive", |_, settings| {
+            Box::new(EvasiveRobber::new(settings.get("Evasive")))
+        });
+        registry.set_robber_settings_ui("Evasive", |ui, settings| {
+            settings.edit("Evasive", |evasive| show_evasive_settings(ui, evasive))
+        });
+        registry.register_robber("Human", |_, _| Box::new(HumanRobber::new()));
+        registry
+    }
+}
 
 pub struct RandomCop {
     number_of_cops: u8,
 }
 
 impl RandomCop {
-    fn new(number_of_cops: u8) -> RandomCop {
+    pub fn new(number_of_cops: u8) -> RandomCop {
         RandomCop { number_of_cops }
     }
+}
 
-    fn start(&mut self, graph: &Graph) -> CopPositions {
+impl CopStrategy for RandomCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
         let mut positions = Vec::new();
-        let mut rng = rand::thread_rng();
         let options = Uniform::from(0..graph.vertices.len());
         for _ in 0..self.number_of_cops {
-            positions.push(options.sample(&mut rng));
+            positions.push(options.sample(rng));
         }
         positions
     }
@@ -79,9 +495,9 @@ impl RandomCop {
         graph: &Graph,
         cop_positions: &CopPositions,
         _robber_position: RobberPosition,
+        rng: &mut GameRng,
     ) -> CopPositions {
         let mut positions = Vec::new();
-        let mut rng = rand::thread_rng();
         for &cop_position in cop_positions {
             let neighbours = &graph.adjacency_list[cop_position];
             // Since we can stay at our current position, we choose a number from 0 to neighbours.len().
@@ -97,17 +513,37 @@ impl RandomCop {
     }
 
     fn end(&mut self, _graph: &Graph, _cop_positions: &CopPositions, _robber_position: usize) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
 }
 
 pub struct RandomRobber {}
 
 impl RandomRobber {
-    fn new() -> RandomRobber {
+    pub fn new() -> RandomRobber {
         RandomRobber {}
     }
+}
+
+impl Default for RandomRobber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    fn start(&mut self, graph: &Graph, _cop_positions: &CopPositions) -> RobberPosition {
-        let mut rng = rand::thread_rng();
+impl RobberStrategy for RandomRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        _cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
         rng.gen_range(0..graph.vertices.len())
     }
 
@@ -116,8 +552,8 @@ impl RandomRobber {
         graph: &Graph,
         _cop_positions: &CopPositions,
         robber_position: RobberPosition,
+        rng: &mut GameRng,
     ) -> RobberPosition {
-        let mut rng = rand::thread_rng();
         let neighbours = &graph.adjacency_list[robber_position];
         let new_position = rng.gen_range(0..=neighbours.len());
         if new_position == neighbours.len() {
@@ -134,305 +570,1025 @@ impl RandomRobber {
         _robber_position: RobberPosition,
     ) {
     }
-}
-
-// A bag of moves for a given position. Used by the MENACE algorithm.
-pub struct Bag {
-    pub counts: Vec<u32>,
-}
-
-impl Bag {
-    fn new(size: usize) -> Bag {
-        Bag {
-            counts: vec![50; size],
-        }
-    }
 
-    fn choose(&self) -> usize {
-        let dist = WeightedIndex::new(&self.counts).unwrap();
-        let mut rng = rand::thread_rng();
-        dist.sample(&mut rng)
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 
-    fn increase(&mut self, value: usize) {
-        self.counts[value] += 3;
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
     }
+}
 
-    fn decrease(&mut self, value: usize) {
-        if self.counts[value] > 0 {
-            self.counts[value] -= 1;
-        }
+/// The reinforcement parameters of a MENACE player.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct MenaceSettings {
+    /// The number of tokens for each move in a new bag, and in a bag which has run out of tokens.
+    pub initial_tokens: u32,
+    /// The tokens added for each move played in a won match.
+    pub win_reward: i32,
+    /// The tokens added for each move played in a lost match. Negative rewards remove tokens.
+    pub loss_reward: i32,
+    /// If set, a match where the robber survives until the steps run out counts as a draw,
+    /// and this is the reward instead of the win/loss reward.
+    pub draw_reward: Option<i32>,
+    /// Whether to scale the reward of each move by how close it was to the end of the match.
+    /// The last move gets the full reward, the first move of a match with n moves gets 1/n of it.
+    pub scale_by_closeness: bool,
+    /// Whether positions which are equivalent under a symmetry of the graph share a bag.
+    pub use_symmetries: bool,
+}
 
-        let total_count: u32 = self.counts.iter().sum();
-        if total_count == 0 {
-            for count in self.counts.iter_mut() {
-                *count = 50;
-            }
+impl Default for MenaceSettings {
+    fn default() -> Self {
+        Self {
+            initial_tokens: 50,
+            win_reward: 3,
+            loss_reward: -1,
+            draw_reward: None,
+            scale_by_closeness: false,
+            use_symmetries: true,
         }
     }
 }
 
-pub struct MenaceCop {
-    number_of_cops: u8,
-    // We use Option<(CopPositions, RobberPosition)>:
-    // None is the key for the bag corresponding to the start state.
-    // Some((cop_positions, robber_position)) corresponds to the non start states.
-    pub bags: HashMap<Option<(CopPositions, RobberPosition)>, Bag>,
-    // We keep track of the moves to increase/decrease.
-    moves: Vec<(Option<(CopPositions, RobberPosition)>, usize)>,
+// A bag of moves for a given position. Used by the MENACE algorithm.
+// Bags can have a huge number of moves (the cop start bag has a move for every placement
+// of the cops), so we only store the counts which differ from the initial number of tokens.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct Bag {
+    number_of_moves: u64,
+    initial_tokens: u32,
+    counts: BTreeMap<u64, u32>,
 }
 
-impl MenaceCop {
-    fn new(number_of_cops: u8) -> Self {
-        Self {
-            number_of_cops,
-            bags: HashMap::new(),
-            moves: Vec::new(),
+impl Bag {
+    fn new(number_of_moves: u64, initial_tokens: u32) -> Bag {
+        Bag {
+            number_of_moves,
+            initial_tokens,
+            counts: BTreeMap::new(),
         }
     }
 
-    fn start(&mut self, graph: &Graph) -> CopPositions {
-        let number_of_vertices = graph.vertices.len();
-        let bag_key = None;
-        let bag = self
-            .bags
-            .entry(bag_key.clone())
-            .or_insert_with(|| Bag::new(number_of_vertices.pow(self.number_of_cops as u32)));
+    pub fn number_of_moves(&self) -> u64 {
+        self.number_of_moves
+    }
 
-        let mut choice = bag.choose();
-        self.moves.push((bag_key, choice));
+    pub fn count(&self, choice: u64) -> u32 {
+        self.counts
+            .get(&choice)
+            .copied()
+            .unwrap_or(self.initial_tokens)
+    }
 
-        let mut position = vec![];
-        for _ in 0..self.number_of_cops {
-            position.push(choice % number_of_vertices);
-            choice /= number_of_vertices;
-        }
-        position
+    /// The moves and their counts in the order of the moves.
+    pub fn counts(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        (0..self.number_of_moves).map(|choice| (choice, self.count(choice)))
     }
 
-    fn step(
-        &mut self,
-        graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) -> CopPositions {
-        let bag_key = Some((cop_positions.clone(), robber_position));
-        let bag = self.bags.entry(bag_key.clone()).or_insert_with(|| {
-            let mut size = 1;
-            for &cop_position in cop_positions {
-                size *= graph.adjacency_list[cop_position].len() + 1;
-            }
-            Bag::new(size)
-        });
+    /// The `limit` moves with the most tokens, in descending order of their counts.
+    pub fn most_tokens(&self, limit: usize) -> Vec<(u64, u32)> {
+        // Every move with the initial count is as good as any other, so we only need the first few.
+        let mut counts: Vec<_> = self
+            .counts()
+            .filter(|&(choice, _)| !self.counts.contains_key(&choice))
+            .take(limit)
+            .chain(self.counts.iter().map(|(&choice, &count)| (choice, count)))
+            .collect();
+        counts.sort_by_key(|&(choice, count)| (std::cmp::Reverse(count), choice));
+        counts.truncate(limit);
+        counts
+    }
 
-        let mut choice = bag.choose();
-        self.moves.push((bag_key, choice));
+    fn total(&self) -> u128 {
+        let changed: u128 = self.counts.values().map(|&count| count as u128).sum();
+        let unchanged = (self.number_of_moves - self.counts.len() as u64) as u128;
+        changed + unchanged * self.initial_tokens as u128
+    }
 
-        let mut position = vec![];
-        for &cop_position in cop_positions {
-            let neighbours = &graph.adjacency_list[cop_position];
-            let new_cop_position = choice % (neighbours.len() + 1);
-            if new_cop_position == neighbours.len() {
-                position.push(cop_position);
-            } else {
-                position.push(neighbours[new_cop_position]);
+    fn choose(&self, rng: &mut GameRng) -> u64 {
+        let mut token = rng.gen_range(0..self.total());
+        for (&choice, &count) in self.counts.iter() {
+            if token < count as u128 {
+                return choice;
             }
-            choice /= neighbours.len() + 1;
+            token -= count as u128;
         }
-        position
-    }
 
-    fn end(
-        &mut self,
-        _graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) {
-        let won = cop_positions.contains(&robber_position);
-        for (position, choice) in self.moves.iter() {
-            // We should've added a corresponding bag if the position is in self.moves, so we can unwrap.
-            let bag = self.bags.get_mut(position).unwrap();
-            if won {
-                bag.increase(*choice);
+        // The token belongs to a move with the initial count. We find the index-th such move
+        // by skipping the moves with changed counts, which are in increasing order.
+        let mut choice = (token / self.initial_tokens as u128) as u64;
+        for &changed_choice in self.counts.keys() {
+            if changed_choice <= choice {
+                choice += 1;
             } else {
-                bag.decrease(*choice);
+                break;
             }
         }
-        self.moves.clear();
+        choice
     }
-}
 
-pub struct MenaceRobber {
-    // We use (CopPositions, Option<RobberPosition>):
-    // (cop_positions, None) is the key for the bag corresponding to the start states.
-    // (cop_positions, Some(robber_position)) corresponds to the non start states.
-    pub bags: HashMap<(CopPositions, Option<RobberPosition>), Bag>,
-    // We keep track of the moves to increase/decrease.
-    moves: Vec<((CopPositions, Option<RobberPosition>), usize)>,
-}
+    // Adds tokens to a move. If the bag runs out of tokens, we reset it.
+    fn add(&mut self, choice: u64, tokens: i32) {
+        let count = self.count(choice);
+        let count = if tokens >= 0 {
+            count.saturating_add(tokens as u32)
+        } else {
+            count.saturating_sub(tokens.unsigned_abs())
+        };
+        if count == self.initial_tokens {
+            self.counts.remove(&choice);
+        } else {
+            self.counts.insert(choice, count);
+        }
 
-impl MenaceRobber {
-    fn new() -> Self {
-        Self {
-            bags: HashMap::new(),
-            moves: Vec::new(),
+        if self.total() == 0 {
+            self.counts.clear();
         }
     }
+}
 
-    fn start(&mut self, graph: &Graph, cop_positions: &CopPositions) -> RobberPosition {
-        let bag_key = (cop_positions.clone(), None);
-        let bag = self.bags.entry(bag_key.clone()).or_insert_with(|| {
-            let number_of_vertices = graph.vertices.len();
-            Bag::new(number_of_vertices)
-        });
+/// A position encoded as a single integer, used as the key of MENACE bags.
+/// The cop positions and the robber position are the digits of a number in base
+/// `vertices + 1`, where the digit `vertices` stands for a player who hasn't chosen
+/// their starting position yet. MENACE sorts the cop positions first, since the cops
+/// are interchangeable.
+pub type StateKey = u64;
 
-        let new_robber_position = bag.choose();
-        self.moves.push((bag_key, new_robber_position));
-        new_robber_position
-    }
+/// Encodes a position as a [`StateKey`]. The cop positions are None before the cops have started.
+pub fn encode_state(
+    graph: &Graph,
+    number_of_cops: u8,
+    cop_positions: Option<&CopPositions>,
+    robber_position: Option<RobberPosition>,
+) -> StateKey {
+    let base = graph.vertices.len() as u64 + 1;
+    let missing = graph.vertices.len();
+    let digits = (0..number_of_cops as usize)
+        .map(|cop| cop_positions.map_or(missing, |cop_positions| cop_positions[cop]))
+        .chain(std::iter::once(robber_position.unwrap_or(missing)));
 
-    fn step(
-        &mut self,
-        graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) -> RobberPosition {
-        let neighbours = &graph.adjacency_list[robber_position];
-        let bag_key = (cop_positions.clone(), Some(robber_position));
-        let bag = self
-            .bags
-            .entry(bag_key.clone())
-            .or_insert_with(|| Bag::new(neighbours.len() + 1));
+    let mut key: StateKey = 0;
+    for digit in digits {
+        key = key
+            .checked_mul(base)
+            .and_then(|key| key.checked_add(digit as u64))
+            .expect("too many positions for MENACE");
+    }
+    key
+}
 
-        let new_robber_position = bag.choose();
-        self.moves.push((bag_key, new_robber_position));
-        if new_robber_position == neighbours.len() {
-            robber_position
-        } else {
-            neighbours[new_robber_position]
-        }
+/// Checks that every position of the graph with the number of cops fits in a [`StateKey`],
+/// which MENACE and Q-learning need. The number of cop moves from a position is smaller than
+/// the number of positions, so the moves fit as well.
+pub fn check_state_encoding(graph: &Graph, number_of_cops: u8) -> Result<(), String> {
+    if number_of_cops <= max_encodable_cops(graph.vertices.len()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The game on {} with {number_of_cops} cops has too many positions to encode.",
+            graph.name
+        ))
     }
+}
 
-    fn end(
-        &mut self,
-        _graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) {
-        let won = !cop_positions.contains(&robber_position);
-        for (position, choice) in self.moves.iter() {
-            let bag = self.bags.get_mut(position).unwrap();
-            if won {
-                bag.increase(*choice);
-            } else {
-                bag.decrease(*choice);
-            }
+/// The largest number of cops whose positions on a graph with the number of vertices fit in
+/// a [`StateKey`]. There are (vertices + 1)^(cops + 1) positions, as positions can be missing.
+pub const fn max_encodable_cops(number_of_vertices: usize) -> u8 {
+    let base = number_of_vertices as u64 + 1;
+    let mut number_of_cops = 0;
+    while number_of_cops < u8::MAX {
+        match base.checked_pow(number_of_cops as u32 + 2) {
+            Some(_) => number_of_cops += 1,
+            None => break,
         }
-        self.moves.clear();
     }
+    number_of_cops
 }
 
-#[derive(PartialEq)]
-pub enum Turn {
-    Cop,
-    Robber,
-    Over,
+/// Checks that the cop moves from every position fit in a u64, which MCTS needs.
+/// There are at most vertices^cops moves, as every cop moves to one of the vertices.
+pub fn check_cop_move_encoding(graph: &Graph, number_of_cops: u8) -> Result<(), String> {
+    match (graph.vertices.len() as u64).checked_pow(number_of_cops as u32) {
+        Some(_) => Ok(()),
+        None => Err(format!(
+            "The cops on {} have too many moves with {number_of_cops} cops to encode.",
+            graph.name
+        )),
+    }
 }
 
-pub enum Cop {
-    Random(RandomCop),
-    Menace(MenaceCop),
-}
+/// Decodes a [`StateKey`] made by [`encode_state`].
+pub fn decode_state(
+    graph: &Graph,
+    number_of_cops: u8,
+    mut key: StateKey,
+) -> (Option<CopPositions>, Option<RobberPosition>) {
+    let base = graph.vertices.len() as u64 + 1;
+    let missing = graph.vertices.len();
+    let mut next_digit = || {
+        let digit = (key % base) as usize;
+        key /= base;
+        (digit != missing).then_some(digit)
+    };
 
-impl Cop {
-    fn start(&mut self, graph: &Graph) -> CopPositions {
-        match self {
-            Cop::Random(cop) => cop.start(graph),
-            Cop::Menace(cop) => cop.start(graph),
+    let robber_position = next_digit();
+    let mut cop_positions = vec![0; number_of_cops as usize];
+    for cop_position in cop_positions.iter_mut().rev() {
+        match next_digit() {
+            Some(digit) => *cop_position = digit,
+            None => return (None, robber_position),
         }
     }
+    (Some(cop_positions), robber_position)
+}
 
-    fn step(
-        &mut self,
-        graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) -> CopPositions {
-        match self {
-            Cop::Random(cop) => cop.step(graph, cop_positions, robber_position),
-            Cop::Menace(cop) => cop.step(graph, cop_positions, robber_position),
-        }
+// The number of ways to choose k of n things, or None if it doesn't fit in a u64.
+fn binomial(n: u64, k: u64) -> Option<u64> {
+    if k > n {
+        return Some(0);
     }
-
-    fn end(&mut self, graph: &Graph, cop_positions: &CopPositions, robber_position: usize) {
-        match self {
-            Cop::Random(cop) => cop.end(graph, cop_positions, robber_position),
-            Cop::Menace(cop) => cop.end(graph, cop_positions, robber_position),
+    let mut result: u128 = 1;
+    for i in 0..k.min(n - k) {
+        // result is C(n, i), so result * (n - i) = C(n, i + 1) * (i + 1) is divisible by i + 1.
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        if result > u64::MAX as u128 {
+            return None;
         }
     }
+    Some(result as u64)
 }
 
-pub enum Robber {
-    Random(RandomRobber),
-    Menace(MenaceRobber),
+// The number of multisets of size elements out of options elements, e.g. the number of
+// ways to move size interchangeable cops which share a vertex with options moves.
+fn number_of_multisets(options: u64, size: u64) -> u64 {
+    if size == 0 {
+        return 1;
+    }
+    binomial(options + size - 1, size).expect("too many positions for MENACE")
 }
 
-impl Robber {
-    fn start(&mut self, graph: &Graph, cop_positions: &CopPositions) -> RobberPosition {
-        match self {
-            Robber::Random(robber) => robber.start(graph, cop_positions),
-            Robber::Menace(robber) => robber.start(graph, cop_positions),
-        }
+// The multiset with the given index in the combinatorial number system, as a sorted list.
+// A sorted multiset a_0 <= .. <= a_(size-1) corresponds to the set of the distinct
+// numbers a_i + i, and the index of the set {b_0 < .. < b_(size-1)} is the sum of C(b_i, i + 1).
+fn multiset_from_index(options: u64, size: u64, mut index: u64) -> Vec<usize> {
+    let mut multiset = vec![0; size as usize];
+    let mut limit = options + size - 1;
+    for i in (0..size).rev() {
+        // The largest b with C(b, i + 1) <= index. C(i, i + 1) = 0, so there is always one.
+        let b = (i..limit)
+            .rev()
+            .find(|&b| binomial(b, i + 1).map_or(false, |count| count <= index))
+            .unwrap();
+        index -= binomial(b, i + 1).unwrap();
+        multiset[i as usize] = (b - i) as usize;
+        limit = b;
     }
+    multiset
+}
 
-    fn step(
-        &mut self,
-        graph: &Graph,
-        cop_positions: &CopPositions,
-        robber_position: RobberPosition,
-    ) -> RobberPosition {
-        match self {
-            Robber::Random(robber) => robber.step(graph, cop_positions, robber_position),
-            Robber::Menace(robber) => robber.step(graph, cop_positions, robber_position),
-        }
+// The cops grouped by their position, in increasing order of the position.
+fn cops_by_position(cop_positions: impl Iterator<Item = usize>) -> BTreeMap<usize, Vec<usize>> {
+    let mut cops: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (cop, cop_position) in cop_positions.enumerate() {
+        cops.entry(cop_position).or_default().push(cop);
     }
+    cops
+}
 
-    fn end(&mut self, graph: &Graph, cop_positions: &CopPositions, robber_position: usize) {
-        match self {
-            Robber::Random(robber) => robber.end(graph, cop_positions, robber_position),
-            Robber::Menace(robber) => robber.end(graph, cop_positions, robber_position),
-        }
+// Rewards the moves played by a MENACE player in a match.
+fn reinforce(
+    bags: &mut HashMap<StateKey, Bag>,
+    moves: &[(StateKey, u64)],
+    won: bool,
+    captured: bool,
+    settings: &MenaceSettings,
+) {
+    let reward = match settings.draw_reward {
+        Some(draw_reward) if !captured => draw_reward,
+        _ if won => settings.win_reward,
+        _ => settings.loss_reward,
+    };
+    for (i, (position, choice)) in moves.iter().enumerate() {
+        let tokens = if settings.scale_by_closeness {
+            (reward as f32 * (i + 1) as f32 / moves.len() as f32).round() as i32
+        } else {
+            reward
+        };
+        // We should've added a corresponding bag if the position is in moves, so we can unwrap.
+        let bag = bags.get_mut(position).unwrap();
+        bag.add(*choice, tokens);
+    }
+}
+
+// Computes the symmetries of the graph a learning player uses, depending on its settings.
+pub(crate) fn learning_symmetries(graph: &Graph, use_symmetries: bool) -> Symmetries {
+    if use_symmetries {
+        Symmetries::new(graph)
+    } else {
+        Symmetries::trivial(graph)
+    }
+}
+
+// The number of cop moves from a position, which is the number of moves of its MENACE bag.
+// The cop positions are None for the start bag.
+// The cops are interchangeable, so the cops on a vertex make a multiset of moves
+// and the start bag has a move for every multiset of starting positions.
+pub(crate) fn cop_number_of_moves(
+    graph: &Graph,
+    number_of_cops: u8,
+    cop_positions: Option<&CopPositions>,
+) -> u64 {
+    match cop_positions {
+        None => number_of_multisets(graph.vertices.len() as u64, number_of_cops as u64),
+        Some(cop_positions) => cops_by_position(cop_positions.iter().copied())
+            .into_iter()
+            .map(|(cop_position, cops)| {
+                let options = graph.adjacency_list[cop_position].len() as u64 + 1;
+                number_of_multisets(options, cops.len() as u64)
+            })
+            .try_fold(1u64, |product, moves| product.checked_mul(moves))
+            .expect("too many positions for MENACE"),
+    }
+}
+
+// Decodes a cop move from the bag of the position the automorphism maps the position to,
+// and maps the move back. The move is a digit for every vertex with cops, in increasing
+// order of the vertex, and each digit is the index of the multiset of moves of its cops,
+// where move i is to the i-th neighbour and the last move is to stay.
+// Each cop keeps its index, so the cops don't swap places in the animation.
+pub(crate) fn decode_cop_move(
+    graph: &Graph,
+    number_of_cops: u8,
+    symmetries: &Symmetries,
+    automorphism: usize,
+    position: Option<(&CopPositions, RobberPosition)>,
+    mut choice: u64,
+) -> CopPositions {
+    let new_cop_positions = match position {
+        None => multiset_from_index(graph.vertices.len() as u64, number_of_cops as u64, choice),
+        Some((cop_positions, _)) => {
+            let mut new_cop_positions = vec![0; cop_positions.len()];
+            let cops = cops_by_position(
+                cop_positions
+                    .iter()
+                    .map(|&cop_position| symmetries.map(automorphism, cop_position)),
+            );
+            for (cop_position, cops) in cops {
+                let neighbours = &graph.adjacency_list[cop_position];
+                let options = neighbours.len() as u64 + 1;
+                let number_of_moves = number_of_multisets(options, cops.len() as u64);
+                let moves =
+                    multiset_from_index(options, cops.len() as u64, choice % number_of_moves);
+                for (cop, new_cop_position) in cops.into_iter().zip(moves) {
+                    new_cop_positions[cop] = neighbours
+                        .get(new_cop_position)
+                        .copied()
+                        .unwrap_or(cop_position);
+                }
+                choice /= number_of_moves;
+            }
+            new_cop_positions
+        }
+    };
+    new_cop_positions
+        .into_iter()
+        .map(|cop_position| symmetries.map_back(automorphism, cop_position))
+        .collect()
+}
+
+// The number of robber moves from a position, which is the number of moves of its MENACE bag.
+// The robber position is None for start bags.
+pub(crate) fn robber_number_of_moves(
+    graph: &Graph,
+    robber_position: Option<RobberPosition>,
+) -> u64 {
+    match robber_position {
+        None => graph.vertices.len() as u64,
+        Some(robber_position) => graph.adjacency_list[robber_position].len() as u64 + 1,
+    }
+}
+
+// Decodes a robber move from the bag of the position the automorphism maps the position to,
+// and maps the move back.
+pub(crate) fn decode_robber_move(
+    graph: &Graph,
+    symmetries: &Symmetries,
+    automorphism: usize,
+    robber_position: Option<RobberPosition>,
+    choice: u64,
+) -> RobberPosition {
+    let choice = choice as usize;
+    let new_robber_position = match robber_position {
+        None => choice,
+        Some(robber_position) => {
+            let robber_position = symmetries.map(automorphism, robber_position);
+            let neighbours = &graph.adjacency_list[robber_position];
+            if choice == neighbours.len() {
+                robber_position
+            } else {
+                neighbours[choice]
+            }
+        }
+    };
+    symmetries.map_back(automorphism, new_robber_position)
+}
+
+pub struct MenaceCop {
+    number_of_cops: u8,
+    settings: MenaceSettings,
+    // Computed when the first match starts. Equivalent positions share the bag of the
+    // position with the smallest key, and moves are mapped through the automorphism.
+    symmetries: Option<Symmetries>,
+    // The bags keyed by the encoded position. The start bag is the position without any players.
+    pub bags: HashMap<StateKey, Bag>,
+    // We keep track of the moves to reward once the match is over.
+    moves: Vec<(StateKey, u64)>,
+}
+
+impl MenaceCop {
+    pub fn new(number_of_cops: u8, settings: MenaceSettings) -> Self {
+        Self {
+            number_of_cops,
+            settings,
+            symmetries: None,
+            bags: HashMap::new(),
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &MenaceSettings {
+        &self.settings
+    }
+
+    /// The symmetries of the graph used to share bags, or only the identity before the first match.
+    pub fn symmetries(&self, graph: &Graph) -> Cow<'_, Symmetries> {
+        match &self.symmetries {
+            Some(symmetries) => Cow::Borrowed(symmetries),
+            None => Cow::Owned(Symmetries::trivial(graph)),
+        }
+    }
+
+    // The key of the bag for a position, and the automorphism which maps the position
+    // to the position of the bag. The position is None for the start bag.
+    fn bag_key(
+        &self,
+        graph: &Graph,
+        symmetries: &Symmetries,
+        position: Option<(&CopPositions, RobberPosition)>,
+    ) -> (StateKey, usize) {
+        symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            position.map(|(cop_positions, _)| cop_positions),
+            position.map(|(_, robber_position)| robber_position),
+        )
+    }
+
+    /// The bag for a position, or a new bag if the position hasn't been played yet.
+    /// The position is None for the start bag.
+    pub fn bag(&self, graph: &Graph, position: Option<(&CopPositions, RobberPosition)>) -> Bag {
+        let (key, _) = self.bag_key(graph, &self.symmetries(graph), position);
+        self.bags.get(&key).cloned().unwrap_or_else(|| {
+            Bag::new(
+                cop_number_of_moves(
+                    graph,
+                    self.number_of_cops,
+                    position.map(|(cop_positions, _)| cop_positions),
+                ),
+                self.settings.initial_tokens,
+            )
+        })
+    }
+
+    /// The cop positions after a move from the bag of a position.
+    /// The position is None for the start bag.
+    pub fn decode_move(
+        &self,
+        graph: &Graph,
+        position: Option<(&CopPositions, RobberPosition)>,
+        choice: u64,
+    ) -> CopPositions {
+        let symmetries = self.symmetries(graph);
+        let (_, automorphism) = self.bag_key(graph, &symmetries, position);
+        decode_cop_move(
+            graph,
+            self.number_of_cops,
+            &symmetries,
+            automorphism,
+            position,
+            choice,
+        )
+    }
+
+    // Chooses a move from the bag for a position, and remembers it to reward it later.
+    fn choose(
+        &mut self,
+        graph: &Graph,
+        position: Option<(&CopPositions, RobberPosition)>,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        let settings = &self.settings;
+        let symmetries = self
+            .symmetries
+            .take()
+            .unwrap_or_else(|| learning_symmetries(graph, settings.use_symmetries));
+        let (bag_key, automorphism) = self.bag_key(graph, &symmetries, position);
+        let number_of_moves = cop_number_of_moves(
+            graph,
+            self.number_of_cops,
+            position.map(|(cop_positions, _)| cop_positions),
+        );
+        let initial_tokens = self.settings.initial_tokens;
+        let bag = self
+            .bags
+            .entry(bag_key)
+            .or_insert_with(|| Bag::new(number_of_moves, initial_tokens));
+
+        let choice = bag.choose(rng);
+        self.moves.push((bag_key, choice));
+        let new_cop_positions = decode_cop_move(
+            graph,
+            self.number_of_cops,
+            &symmetries,
+            automorphism,
+            position,
+            choice,
+        );
+        self.symmetries = Some(symmetries);
+        new_cop_positions
+    }
+
+    /// The trained bags, to be saved and loaded later with load_brain.
+    pub fn brain(&self, graph: &Graph) -> MenaceBrain {
+        MenaceBrain::Cop {
+            graph: graph.clone(),
+            number_of_cops: self.number_of_cops,
+            bags: self
+                .bags
+                .iter()
+                .map(|(&bag_key, bag)| {
+                    let position = match decode_state(graph, self.number_of_cops, bag_key) {
+                        (Some(cop_positions), Some(robber_position)) => {
+                            Some((cop_positions, robber_position))
+                        }
+                        _ => None,
+                    };
+                    (position, bag.clone())
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the bags with the bags of a brain trained on the same graph.
+    /// The moves of the current match are forgotten.
+    pub fn load_brain(&mut self, graph: &Graph, brain: MenaceBrain) -> Result<(), String> {
+        let MenaceBrain::Cop {
+            graph: brain_graph,
+            number_of_cops,
+            bags,
+        } = brain
+        else {
+            return Err("This is a MENACE robber brain.".to_string());
+        };
+        check_brain_graph(&brain_graph, number_of_cops, graph, self.number_of_cops)?;
+
+        // The brain may have been trained without symmetries, so we only keep
+        // the bags of canonical positions, whose cops are also sorted.
+        let symmetries = learning_symmetries(graph, self.settings.use_symmetries);
+        let mut new_bags = HashMap::new();
+        for (position, bag) in bags {
+            if let Some((cop_positions, robber_position)) = &position {
+                check_positions(graph, cop_positions, Some(*robber_position), number_of_cops)?;
+            }
+            let cop_positions = position.as_ref().map(|(cop_positions, _)| cop_positions);
+            check_bag(
+                &bag,
+                cop_number_of_moves(graph, self.number_of_cops, cop_positions),
+            )?;
+            let robber_position = position
+                .as_ref()
+                .map(|&(_, robber_position)| robber_position);
+            let (key, _) =
+                symmetries.canonical_state(graph, number_of_cops, cop_positions, robber_position);
+            if key == encode_state(graph, number_of_cops, cop_positions, robber_position) {
+                new_bags.insert(key, bag);
+            }
+        }
+
+        self.symmetries = Some(symmetries);
+        self.bags = new_bags;
+        self.moves.clear();
+        Ok(())
+    }
+}
+
+impl CopStrategy for MenaceCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        self.choose(graph, None, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        self.choose(graph, Some((cop_positions, robber_position)), rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) {
+        let captured = cop_positions.contains(&robber_position);
+        reinforce(
+            &mut self.bags,
+            &self.moves,
+            captured,
+            captured,
+            &self.settings,
+        );
+        self.moves.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub struct MenaceRobber {
+    number_of_cops: u8,
+    settings: MenaceSettings,
+    // Computed when the first match starts, see MenaceCop.
+    symmetries: Option<Symmetries>,
+    // The bags keyed by the encoded position. The start bags are the positions without the robber.
+    pub bags: HashMap<StateKey, Bag>,
+    // We keep track of the moves to reward once the match is over.
+    moves: Vec<(StateKey, u64)>,
+}
+
+impl MenaceRobber {
+    pub fn new(number_of_cops: u8, settings: MenaceSettings) -> Self {
+        Self {
+            number_of_cops,
+            settings,
+            symmetries: None,
+            bags: HashMap::new(),
+            moves: Vec::new(),
+        }
+    }
+
+    pub fn settings(&self) -> &MenaceSettings {
+        &self.settings
+    }
+
+    /// The symmetries of the graph used to share bags, or only the identity before the first match.
+    pub fn symmetries(&self, graph: &Graph) -> Cow<'_, Symmetries> {
+        match &self.symmetries {
+            Some(symmetries) => Cow::Borrowed(symmetries),
+            None => Cow::Owned(Symmetries::trivial(graph)),
+        }
+    }
+
+    /// The bag for a position, or a new bag if the position hasn't been played yet.
+    /// The robber position is None for start bags.
+    pub fn bag(
+        &self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+    ) -> Bag {
+        let (key, _) = self.symmetries(graph).canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        self.bags.get(&key).cloned().unwrap_or_else(|| {
+            Bag::new(
+                robber_number_of_moves(graph, robber_position),
+                self.settings.initial_tokens,
+            )
+        })
+    }
+
+    /// The robber position after a move from the bag of a position.
+    /// The robber position is None for start bags.
+    pub fn decode_move(
+        &self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+        choice: u64,
+    ) -> RobberPosition {
+        let symmetries = self.symmetries(graph);
+        let (_, automorphism) = symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        decode_robber_move(graph, &symmetries, automorphism, robber_position, choice)
+    }
+
+    // Chooses a move from the bag for a position, and remembers it to reward it later.
+    fn choose(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let settings = &self.settings;
+        let symmetries = self
+            .symmetries
+            .take()
+            .unwrap_or_else(|| learning_symmetries(graph, settings.use_symmetries));
+        let (bag_key, automorphism) = symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        let number_of_moves = robber_number_of_moves(graph, robber_position);
+        let initial_tokens = self.settings.initial_tokens;
+        let bag = self
+            .bags
+            .entry(bag_key)
+            .or_insert_with(|| Bag::new(number_of_moves, initial_tokens));
+
+        let choice = bag.choose(rng);
+        self.moves.push((bag_key, choice));
+        let new_robber_position =
+            decode_robber_move(graph, &symmetries, automorphism, robber_position, choice);
+        self.symmetries = Some(symmetries);
+        new_robber_position
+    }
+
+    /// The trained bags, to be saved and loaded later with load_brain.
+    pub fn brain(&self, graph: &Graph) -> MenaceBrain {
+        MenaceBrain::Robber {
+            graph: graph.clone(),
+            number_of_cops: self.number_of_cops,
+            bags: self
+                .bags
+                .iter()
+                .map(|(&bag_key, bag)| {
+                    let (cop_positions, robber_position) =
+                        decode_state(graph, self.number_of_cops, bag_key);
+                    // Robber bags always have cop positions.
+                    (
+                        (cop_positions.unwrap_or_default(), robber_position),
+                        bag.clone(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the bags with the bags of a brain trained on the same graph.
+    /// The moves of the current match are forgotten.
+    pub fn load_brain(&mut self, graph: &Graph, brain: MenaceBrain) -> Result<(), String> {
+        let MenaceBrain::Robber {
+            graph: brain_graph,
+            number_of_cops,
+            bags,
+        } = brain
+        else {
+            return Err("This is a MENACE cop brain.".to_string());
+        };
+        check_brain_graph(&brain_graph, number_of_cops, graph, self.number_of_cops)?;
+
+        // As for MenaceCop, we only keep the bags of canonical positions.
+        let symmetries = learning_symmetries(graph, self.settings.use_symmetries);
+        let mut new_bags = HashMap::new();
+        for ((cop_positions, robber_position), bag) in bags {
+            check_positions(graph, &cop_positions, robber_position, number_of_cops)?;
+            check_bag(&bag, robber_number_of_moves(graph, robber_position))?;
+            let (key, _) = symmetries.canonical_state(
+                graph,
+                number_of_cops,
+                Some(&cop_positions),
+                robber_position,
+            );
+            if key == encode_state(graph, number_of_cops, Some(&cop_positions), robber_position) {
+                new_bags.insert(key, bag);
+            }
+        }
+
+        self.symmetries = Some(symmetries);
+        self.bags = new_bags;
+        self.moves.clear();
+        Ok(())
+    }
+}
+
+impl RobberStrategy for MenaceRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.choose(graph, cop_positions, None, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.choose(graph, cop_positions, Some(robber_position), rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) {
+        let captured = cop_positions.contains(&robber_position);
+        reinforce(
+            &mut self.bags,
+            &self.moves,
+            !captured,
+            captured,
+            &self.settings,
+        );
+        self.moves.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The trained bags of a MENACE player, with the graph and number of cops they were trained for.
+/// We store the bags as a list keyed by the decoded positions, so brains are readable
+/// and don't depend on the state encoding.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub enum MenaceBrain {
+    Cop {
+        graph: Graph,
+        number_of_cops: u8,
+        bags: Vec<(Option<(CopPositions, RobberPosition)>, Bag)>,
+    },
+    Robber {
+        graph: Graph,
+        number_of_cops: u8,
+        bags: Vec<((CopPositions, Option<RobberPosition>), Bag)>,
+    },
+}
+
+// The checks below make sure a loaded brain can't make a MENACE player panic.
+
+fn check_brain_graph(
+    brain_graph: &Graph,
+    brain_number_of_cops: u8,
+    graph: &Graph,
+    number_of_cops: u8,
+) -> Result<(), String> {
+    if brain_graph.adjacency_list != graph.adjacency_list {
+        return Err(format!(
+            "The brain was trained on a different graph ({}).",
+            brain_graph.name
+        ));
+    }
+    if brain_number_of_cops != number_of_cops {
+        return Err(format!(
+            "The brain was trained for {brain_number_of_cops} cops, but the game has {number_of_cops} cops."
+        ));
+    }
+    Ok(())
+}
+
+fn check_positions(
+    graph: &Graph,
+    cop_positions: &CopPositions,
+    robber_position: Option<RobberPosition>,
+    number_of_cops: u8,
+) -> Result<(), String> {
+    let number_of_vertices = graph.vertices.len();
+    if cop_positions.len() != number_of_cops as usize
+        || cop_positions
+            .iter()
+            .chain(robber_position.iter())
+            .any(|&position| position >= number_of_vertices)
+    {
+        return Err("The brain has a bag for an invalid position.".to_string());
+    }
+    Ok(())
+}
+
+fn check_bag(bag: &Bag, number_of_moves: u64) -> Result<(), String> {
+    if bag.number_of_moves != number_of_moves
+        || bag.initial_tokens == 0
+        || bag.counts.keys().any(|&choice| choice >= number_of_moves)
+        || bag.total() == 0
+    {
+        return Err("The brain has an invalid bag.".to_string());
+    }
+    Ok(())
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Turn {
+    Cop,
+    Robber,
+    Over,
+}
+
+/// The number of finished matches a game keeps the records of, unless it's changed with
+/// [`Game::set_max_recorded_matches`].
+pub const DEFAULT_MAX_RECORDED_MATCHES: usize = 100;
+
+/// The positions of the players after a move.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct RecordedTurn {
+    pub cop_positions: CopPositions,
+    /// None after the cops' starting move, as the robber hasn't started yet.
+    pub robber_position: Option<RobberPosition>,
+}
+
+/// A match played by `Game::update`, as the positions after every move.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MatchRecord {
+    /// The number of the match in its game, starting from 1.
+    pub match_number: u32,
+    pub turns: Vec<RecordedTurn>,
+    pub cop_won: bool,
+}
+
+/// The outcome of a match, for the statistics of a game.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MatchResult {
+    pub match_number: u32,
+    pub cop_won: bool,
+    /// The number of moves of both sides, the starting moves included, until the robber
+    /// was caught. None if the robber escaped.
+    pub capture_turn: Option<usize>,
+    pub cop_start_positions: CopPositions,
+    pub robber_start_position: RobberPosition,
+}
+
+impl MatchRecord {
+    pub fn result(&self) -> MatchResult {
+        MatchResult {
+            match_number: self.match_number,
+            cop_won: self.cop_won,
+            capture_turn: self.cop_won.then_some(self.turns.len()),
+            cop_start_positions: self.turns[0].cop_positions.clone(),
+            // A match ends at the earliest when the robber starts, so the second turn exists
+            // and has the robber's position.
+            robber_start_position: self.turns[1].robber_position.unwrap(),
+        }
     }
 }
 
 pub struct Game {
     pub graph: Graph,
     pub number_of_steps: u8,
-    pub cop: Cop,
-    pub robber: Robber,
+    pub cop: Box<dyn CopStrategy>,
+    pub robber: Box<dyn RobberStrategy>,
     pub score: [u32; 2],
     pub cop_positions: Option<CopPositions>,
     pub robber_position: Option<RobberPosition>,
     pub steps_left: u8,
     pub turn: Turn,
+    pub seed: u64,
+    /// The last finished matches, oldest first.
+    pub records: VecDeque<MatchRecord>,
+    /// The number of finished matches whose records we keep, 0 to not record matches.
+    pub max_recorded_matches: usize,
+    // The turns of the match being played.
+    current_turns: Vec<RecordedTurn>,
+    rng: GameRng,
 }
 
 impl Game {
     pub fn new(
         graph: &Graph,
-        number_of_cops: u8,
         number_of_steps: u8,
-        cop: Algorithm,
-        robber: Algorithm,
+        cop: Box<dyn CopStrategy>,
+        robber: Box<dyn RobberStrategy>,
+        seed: u64,
     ) -> Game {
-        let cop = match cop {
-            Algorithm::Random => Cop::Random(RandomCop::new(number_of_cops)),
-            Algorithm::Menace => Cop::Menace(MenaceCop::new(number_of_cops)),
-        };
-        let robber = match robber {
-            Algorithm::Random => Robber::Random(RandomRobber::new()),
-            Algorithm::Menace => Robber::Menace(MenaceRobber::new()),
-        };
         Game {
             graph: graph.clone(),
             number_of_steps,
@@ -443,65 +1599,84 @@ impl Game {
             robber_position: None,
             steps_left: number_of_steps,
             turn: Turn::Cop,
+            seed,
+            records: VecDeque::new(),
+            max_recorded_matches: DEFAULT_MAX_RECORDED_MATCHES,
+            current_turns: vec![],
+            rng: GameRng::seed_from_u64(seed),
+        }
+    }
+
+    // Adds the current positions to the record of the match, and moves the record to
+    // the finished records if the match is over.
+    fn record_turn(&mut self) {
+        if self.max_recorded_matches == 0 {
+            return;
+        }
+        // We only record after a move, and the cops move first.
+        let cop_positions = self.cop_positions.clone().unwrap();
+        // The match is over with the robber on a cop's vertex if the cops won.
+        let cop_won = self.robber_position.map_or(false, |robber_position| {
+            cop_positions.contains(&robber_position)
+        });
+        self.current_turns.push(RecordedTurn {
+            cop_positions,
+            robber_position: self.robber_position,
+        });
+        if self.turn == Turn::Over {
+            while self.records.len() >= self.max_recorded_matches {
+                self.records.pop_front();
+            }
+            self.records.push_back(MatchRecord {
+                match_number: self.score[0] + self.score[1],
+                turns: std::mem::take(&mut self.current_turns),
+                cop_won,
+            });
+        }
+    }
+
+    /// Changes the number of finished matches whose records we keep, dropping the oldest
+    /// records over it. It's at least 1, as the match being played is recorded from its start.
+    pub fn set_max_recorded_matches(&mut self, max_recorded_matches: usize) {
+        self.max_recorded_matches = max_recorded_matches.max(1);
+        while self.records.len() > self.max_recorded_matches {
+            self.records.pop_front();
+        }
+    }
+
+    /// Whether the next update asks a player for a move which can take long to choose,
+    /// see [`CopStrategy::is_slow`].
+    pub fn next_move_is_slow(&self) -> bool {
+        match self.turn {
+            Turn::Cop => self.cop.is_slow(),
+            Turn::Robber => self.robber.is_slow(),
+            Turn::Over => false,
         }
     }
 
     pub fn update(&mut self) {
         match self.turn {
             Turn::Cop => {
-                if let Some(cop_positions) = &self.cop_positions {
-                    let robber_position = self.robber_position.unwrap(); // Robber position will exist as we have cop_positions and it's a cop turn.
-                    let new_cop_positions =
-                        self.cop.step(&self.graph, cop_positions, robber_position);
-                    if new_cop_positions.contains(&robber_position) {
-                        // Cop won
-                        self.cop
-                            .end(&self.graph, &new_cop_positions, robber_position);
-                        self.robber
-                            .end(&self.graph, &new_cop_positions, robber_position);
-                        self.score[0] += 1;
-                        self.turn = Turn::Over;
-                    } else {
-                        self.turn = Turn::Robber;
-                    }
-                    self.cop_positions = Some(new_cop_positions);
-                } else {
-                    self.cop_positions = Some(self.cop.start(&self.graph));
-                    self.turn = Turn::Robber;
-                }
+                self.cop.set_steps_left(self.steps_left);
+                let new_cop_positions = choose_cop_move(
+                    self.cop.as_mut(),
+                    &self.graph,
+                    self.cop_positions.as_ref(),
+                    self.robber_position,
+                    &mut self.rng,
+                );
+                self.play_cop_move(new_cop_positions);
             }
             Turn::Robber => {
-                let cop_positions = self.cop_positions.as_ref().unwrap(); // Since it's a robber turn, cop_positions will not be None.
-
-                let new_robber_position = if let Some(robber_position) = self.robber_position {
-                    self.steps_left -= 1; // Decrease by one as robber made their move.
-                    self.robber
-                        .step(&self.graph, cop_positions, robber_position)
-                } else {
-                    // We don't decrease by one as the robber just chooses their starting position.
-                    self.robber.start(&self.graph, cop_positions)
-                };
-
-                if cop_positions.contains(&new_robber_position) {
-                    // Cop won
-                    self.cop
-                        .end(&self.graph, cop_positions, new_robber_position);
-                    self.robber
-                        .end(&self.graph, cop_positions, new_robber_position);
-                    self.score[0] += 1;
-                    self.turn = Turn::Over;
-                } else if self.steps_left == 0 {
-                    // Robber won
-                    self.cop
-                        .end(&self.graph, cop_positions, new_robber_position);
-                    self.robber
-                        .end(&self.graph, cop_positions, new_robber_position);
-                    self.score[1] += 1;
-                    self.turn = Turn::Over;
-                } else {
-                    self.turn = Turn::Cop;
-                }
-                self.robber_position = Some(new_robber_position);
+                self.robber.set_steps_left(self.steps_left);
+                let new_robber_position = choose_robber_move(
+                    self.robber.as_mut(),
+                    &self.graph,
+                    self.cop_positions.as_ref().unwrap(), // Since it's a robber turn, cop_positions will not be None.
+                    self.robber_position,
+                    &mut self.rng,
+                );
+                self.play_robber_move(new_robber_position);
             }
             Turn::Over => {
                 self.cop_positions = None;
@@ -511,4 +1686,489 @@ impl Game {
             }
         }
     }
+
+    /// Takes the player whose turn it is out of the game, with what it needs to choose its
+    /// move, so the move can be chosen without borrowing the game. Returns None if the match
+    /// is over. The game mustn't be updated until the mover is put back with
+    /// [`Game::play_move`].
+    pub fn take_mover(&mut self) -> Option<Mover> {
+        let player = match self.turn {
+            Turn::Cop => {
+                self.cop.set_steps_left(self.steps_left);
+                Player::Cop(std::mem::replace(&mut self.cop, Box::new(TakenPlayer)))
+            }
+            Turn::Robber => {
+                self.robber.set_steps_left(self.steps_left);
+                Player::Robber(std::mem::replace(&mut self.robber, Box::new(TakenPlayer)))
+            }
+            Turn::Over => return None,
+        };
+        Some(Mover {
+            player,
+            graph: self.graph.clone(),
+            cop_positions: self.cop_positions.clone(),
+            robber_position: self.robber_position,
+            // The mover continues the game's random numbers, so the game plays the same
+            // whether its moves are chosen by update or by a mover.
+            rng: std::mem::replace(&mut self.rng, GameRng::seed_from_u64(0)),
+        })
+    }
+
+    /// Puts a mover taken with [`Game::take_mover`] back into the game and plays its move.
+    pub fn play_move(&mut self, chosen_move: ChosenMove) {
+        let ChosenMove {
+            player,
+            rng,
+            new_positions,
+        } = chosen_move;
+        self.rng = rng;
+        match (player, new_positions) {
+            (Player::Cop(cop), NewPositions::Cops(new_cop_positions)) => {
+                self.cop = cop;
+                self.play_cop_move(new_cop_positions);
+            }
+            (Player::Robber(robber), NewPositions::Robber(new_robber_position)) => {
+                self.robber = robber;
+                self.play_robber_move(new_robber_position);
+            }
+            _ => unreachable!("a mover chooses the positions of its own side"),
+        }
+    }
+
+    fn play_cop_move(&mut self, new_cop_positions: CopPositions) {
+        if self.cop_positions.is_some() {
+            let robber_position = self.robber_position.unwrap(); // Robber position will exist as we have cop_positions and it's a cop turn.
+            if new_cop_positions.contains(&robber_position) {
+                // Cop won
+                self.cop
+                    .end(&self.graph, &new_cop_positions, robber_position);
+                self.robber
+                    .end(&self.graph, &new_cop_positions, robber_position);
+                self.score[0] += 1;
+                self.turn = Turn::Over;
+            } else {
+                self.turn = Turn::Robber;
+            }
+        } else {
+            self.turn = Turn::Robber;
+        }
+        self.cop_positions = Some(new_cop_positions);
+        self.record_turn();
+    }
+
+    fn play_robber_move(&mut self, new_robber_position: RobberPosition) {
+        let cop_positions = self.cop_positions.as_ref().unwrap(); // Since it's a robber turn, cop_positions will not be None.
+                                                                  // We don't decrease steps_left when the robber just chose their starting position.
+        if self.robber_position.is_some() {
+            self.steps_left -= 1; // Decrease by one as robber made their move.
+        }
+
+        if cop_positions.contains(&new_robber_position) {
+            // Cop won
+            self.cop
+                .end(&self.graph, cop_positions, new_robber_position);
+            self.robber
+                .end(&self.graph, cop_positions, new_robber_position);
+            self.score[0] += 1;
+            self.turn = Turn::Over;
+        } else if self.steps_left == 0 {
+            // Robber won
+            self.cop
+                .end(&self.graph, cop_positions, new_robber_position);
+            self.robber
+                .end(&self.graph, cop_positions, new_robber_position);
+            self.score[1] += 1;
+            self.turn = Turn::Over;
+        } else {
+            self.turn = Turn::Cop;
+        }
+        self.robber_position = Some(new_robber_position);
+        self.record_turn();
+    }
+}
+
+// The starting positions of the cops, or their next positions once they've started.
+fn choose_cop_move(
+    cop: &mut dyn CopStrategy,
+    graph: &Graph,
+    cop_positions: Option<&CopPositions>,
+    robber_position: Option<RobberPosition>,
+    rng: &mut GameRng,
+) -> CopPositions {
+    match cop_positions {
+        Some(cop_positions) => cop.step(graph, cop_positions, robber_position.unwrap(), rng),
+        None => cop.start(graph, rng),
+    }
+}
+
+// The starting position of the robber, or its next position once it has started.
+fn choose_robber_move(
+    robber: &mut dyn RobberStrategy,
+    graph: &Graph,
+    cop_positions: &CopPositions,
+    robber_position: Option<RobberPosition>,
+    rng: &mut GameRng,
+) -> RobberPosition {
+    match robber_position {
+        Some(robber_position) => robber.step(graph, cop_positions, robber_position, rng),
+        None => robber.start(graph, cop_positions, rng),
+    }
+}
+
+enum Player {
+    Cop(Box<dyn CopStrategy>),
+    Robber(Box<dyn RobberStrategy>),
+}
+
+enum NewPositions {
+    Cops(CopPositions),
+    Robber(RobberPosition),
+}
+
+/// The player whose turn it is, taken out of its game by [`Game::take_mover`] to choose its
+/// move without borrowing the game, e.g. so the app can show the game while a slow player thinks.
+pub struct Mover {
+    player: Player,
+    graph: Graph,
+    cop_positions: Option<CopPositions>,
+    robber_position: Option<RobberPosition>,
+    rng: GameRng,
+}
+
+impl Mover {
+    /// Chooses the move, which is played by [`Game::play_move`].
+    pub fn choose(self) -> ChosenMove {
+        let Mover {
+            mut player,
+            graph,
+            cop_positions,
+            robber_position,
+            mut rng,
+        } = self;
+        let new_positions = match &mut player {
+            Player::Cop(cop) => NewPositions::Cops(choose_cop_move(
+                cop.as_mut(),
+                &graph,
+                cop_positions.as_ref(),
+                robber_position,
+                &mut rng,
+            )),
+            Player::Robber(robber) => NewPositions::Robber(choose_robber_move(
+                robber.as_mut(),
+                &graph,
+                cop_positions.as_ref().unwrap(), // The cops start first.
+                robber_position,
+                &mut rng,
+            )),
+        };
+        ChosenMove {
+            player,
+            rng,
+            new_positions,
+        }
+    }
+}
+
+/// A move chosen by a [`Mover`], with the player to put back into the game.
+pub struct ChosenMove {
+    player: Player,
+    rng: GameRng,
+    new_positions: NewPositions,
+}
+
+// Stands in for a player taken out of the game by a mover until the move is played.
+struct TakenPlayer;
+
+impl CopStrategy for TakenPlayer {
+    fn start(&mut self, _graph: &Graph, _rng: &mut GameRng) -> CopPositions {
+        unreachable!("the cop was taken out of the game to choose its move")
+    }
+
+    fn step(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+        _rng: &mut GameRng,
+    ) -> CopPositions {
+        unreachable!("the cop was taken out of the game to choose its move")
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl RobberStrategy for TakenPlayer {
+    fn start(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _rng: &mut GameRng,
+    ) -> RobberPosition {
+        unreachable!("the robber was taken out of the game to choose its move")
+    }
+
+    fn step(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+        _rng: &mut GameRng,
+    ) -> RobberPosition {
+        unreachable!("the robber was taken out of the game to choose its move")
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{heuristics::EvasiveSettings, mcts::MctsSettings};
+
+    // The positions after every update of 50 matches on the Hexagon with 2 cops.
+    fn trajectory(
+        cop: &str,
+        robber: &str,
+        seed: u64,
+    ) -> Vec<(Option<CopPositions>, Option<RobberPosition>)> {
+        let registry = StrategyRegistry::default();
+        let mut settings = StrategySettings::default();
+        settings.edit("MCTS", |mcts: &mut MctsSettings| mcts.rollouts = 50);
+        let cop = registry.create_cop(cop, 2, &settings).unwrap();
+        let robber = registry.create_robber(robber, 2, &settings).unwrap();
+        let mut game = Game::new(&template_graphs()[2], 5, cop, robber, seed);
+        let mut trajectory = Vec::new();
+        while game.score[0] + game.score[1] < 50 {
+            game.update();
+            trajectory.push((game.cop_positions.clone(), game.robber_position));
+        }
+        trajectory
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_matches() {
+        for (cop, robber) in [
+            ("Random", "Random"),
+            ("Menace", "Menace"),
+            ("Q-learning", "Q-learning"),
+            ("Optimal", "Random"),
+            ("Random", "Optimal"),
+            ("Chaser", "Evasive"),
+            ("MCTS", "Evasive"),
+            ("Chaser", "MCTS"),
+        ] {
+            assert_eq!(
+                trajectory(cop, robber, 42),
+                trajectory(cop, robber, 42),
+                "{cop} against {robber}"
+            );
+        }
+    }
+
+    #[test]
+    fn movers_play_like_updates() {
+        let registry = StrategyRegistry::default();
+        let settings = StrategySettings::default();
+        let cop = registry.create_cop("Menace", 2, &settings).unwrap();
+        let robber = registry.create_robber("Q-learning", 2, &settings).unwrap();
+        let mut game = Game::new(&template_graphs()[2], 5, cop, robber, 42);
+        let mut positions = Vec::new();
+        while game.score[0] + game.score[1] < 50 {
+            match game.take_mover() {
+                Some(mover) => game.play_move(mover.choose()),
+                None => game.update(),
+            }
+            positions.push((game.cop_positions.clone(), game.robber_position));
+        }
+        assert_eq!(positions, trajectory("Menace", "Q-learning", 42));
+    }
+
+    #[test]
+    fn different_seeds_play_different_matches() {
+        assert_ne!(
+            trajectory("Random", "Random", 1),
+            trajectory("Random", "Random", 2)
+        );
+    }
+
+    #[test]
+    fn settings_are_kept_by_strategy() {
+        let mut settings = StrategySettings::default();
+        assert_eq!(
+            settings.get::<MctsSettings>("MCTS"),
+            MctsSettings::default()
+        );
+
+        settings.edit("MCTS", |mcts: &mut MctsSettings| mcts.rollouts = 7);
+        settings.edit("Evasive", |_: &mut EvasiveSettings| {});
+        assert_eq!(settings.get::<MctsSettings>("MCTS").rollouts, 7);
+        // Unchanged settings aren't stored.
+        assert_eq!(settings.0.len(), 1);
+        // Settings saved with another type are replaced by the default.
+        assert_eq!(
+            settings.get::<MenaceSettings>("MCTS"),
+            MenaceSettings::default()
+        );
+
+        let json = serde_json::to_string(&settings).unwrap();
+        assert_eq!(
+            serde_json::from_str::<StrategySettings>(&json).unwrap(),
+            settings
+        );
+    }
+
+    #[test]
+    fn registering_a_strategy_replaces_its_settings_ui() {
+        let mut registry = StrategyRegistry::default();
+        assert!(registry
+            .cop_settings_uis
+            .iter()
+            .any(|(name, _)| name == "MCTS"));
+        registry.register_cop("MCTS", |number_of_cops, _| {
+            Box::new(RandomCop::new(number_of_cops))
+        });
+        assert!(!registry
+            .cop_settings_uis
+            .iter()
+            .any(|(name, _)| name == "MCTS"));
+    }
+
+    #[test]
+    fn records_the_last_matches() {
+        let registry = StrategyRegistry::default();
+        let settings = StrategySettings::default();
+        let cop = registry.create_cop("Random", 2, &settings).unwrap();
+        let robber = registry.create_robber("Random", 2, &settings).unwrap();
+        let mut game = Game::new(&template_graphs()[2], 5, cop, robber, 7);
+        game.max_recorded_matches = 3;
+        let mut turns = vec![];
+        while game.score[0] + game.score[1] < 10 {
+            game.update();
+            // The update after a match is over clears the board without a move.
+            let Some(cop_positions) = game.cop_positions.clone() else {
+                continue;
+            };
+            let cop_won = game.robber_position.map_or(false, |robber_position| {
+                cop_positions.contains(&robber_position)
+            });
+            turns.push(RecordedTurn {
+                cop_positions,
+                robber_position: game.robber_position,
+            });
+            if game.turn == Turn::Over {
+                let record = game.records.back().unwrap();
+                assert_eq!(record.turns, turns);
+                assert_eq!(record.cop_won, cop_won);
+                turns.clear();
+            }
+        }
+        let match_numbers: Vec<_> = game
+            .records
+            .iter()
+            .map(|record| record.match_number)
+            .collect();
+        assert_eq!(match_numbers, vec![8, 9, 10]);
+        let cop_wins = game.records.iter().filter(|record| record.cop_won).count();
+        assert!(cop_wins as u32 <= game.score[0]);
+
+        game.set_max_recorded_matches(2);
+        assert_eq!(game.records.len(), 2);
+        assert_eq!(game.records[0].match_number, 9);
+    }
+
+    #[test]
+    fn state_keys_round_trip() {
+        let graph = &template_graphs()[1];
+        for number_of_cops in 1..=3u8 {
+            let number_of_positions = 5usize.pow(number_of_cops as u32);
+            let all_cop_positions = (0..number_of_positions).map(|mut index| {
+                (0..number_of_cops)
+                    .map(|_| {
+                        let cop_position = index % 5;
+                        index /= 5;
+                        cop_position
+                    })
+                    .collect::<CopPositions>()
+            });
+            let mut positions = vec![(None, None)];
+            for cop_positions in all_cop_positions {
+                positions.push((Some(cop_positions.clone()), None));
+                for robber_position in 0..5 {
+                    positions.push((Some(cop_positions.clone()), Some(robber_position)));
+                }
+            }
+
+            let mut keys = HashMap::new();
+            for (cop_positions, robber_position) in positions {
+                let key = encode_state(
+                    graph,
+                    number_of_cops,
+                    cop_positions.as_ref(),
+                    robber_position,
+                );
+                assert_eq!(
+                    decode_state(graph, number_of_cops, key),
+                    (cop_positions.clone(), robber_position)
+                );
+                // Different positions have different keys.
+                assert_eq!(keys.insert(key, (cop_positions, robber_position)), None);
+            }
+        }
+    }
+
+    #[test]
+    fn state_keys_must_fit_in_a_u64() {
+        let graph = Graph {
+            name: "Path200".to_string(),
+            vertices: vec![(0.5, 0.5); 200],
+            adjacency_list: (0..200)
+                .map(|v: usize| {
+                    [v.checked_sub(1), (v < 199).then_some(v + 1)]
+                        .into_iter()
+                        .flatten()
+                        .collect()
+                })
+                .collect(),
+        };
+        assert!(check_state_encoding(&graph, 7).is_ok());
+        assert!(check_state_encoding(&graph, 8).is_err());
+        assert_eq!(max_encodable_cops(200), 7);
+        assert_eq!(max_encodable_cops(100), 8);
+        assert!(check_cop_move_encoding(&graph, 8).is_ok());
+
+        let registry = StrategyRegistry::default();
+        assert!(registry.check_cop("Menace", &graph, 8).is_err());
+        assert!(registry.check_robber("Menace", &graph, 7).is_ok());
+        assert!(registry.check_robber("Q-learning", &graph, 8).is_err());
+        assert!(registry.check_cop("MCTS", &graph, 8).is_ok());
+        assert!(registry.check_cop("Random", &graph, 8).is_ok());
+    }
 }
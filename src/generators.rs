@@ -0,0 +1,920 @@
+use crate::{
+    game::{GameRng, Graph},
+    layouts::force_directed,
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+// The margin around the vertices of a generated graph, so they aren't drawn on the border.
+const MARGIN: f32 = 0.1;
+// The number of times we start pairing the stubs of a random regular graph again after
+// getting stuck, before giving up.
+const MAX_REGULAR_ATTEMPTS: usize = 100;
+// The number of random pairs of stubs we try before checking whether any pair can be joined.
+const MAX_PAIRING_TRIES: usize = 100;
+
+/// A family of graphs with its parameters, which generates a laid-out graph.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphGenerator {
+    Grid {
+        rows: usize,
+        columns: usize,
+    },
+    Torus {
+        rows: usize,
+        columns: usize,
+    },
+    Cycle {
+        length: usize,
+    },
+    Complete {
+        size: usize,
+    },
+    CompleteBipartite {
+        left: usize,
+        right: usize,
+    },
+    Hypercube {
+        dimension: usize,
+    },
+    Petersen,
+    Dodecahedron,
+    /// A tree chosen uniformly at random among the labelled trees.
+    RandomTree {
+        size: usize,
+        seed: u64,
+    },
+    /// The Erdős–Rényi graph G(n, p), where every pair of vertices is joined with probability p.
+    ErdosRenyi {
+        vertices: usize,
+        probability: f32,
+        seed: u64,
+    },
+    /// A graph chosen (almost) uniformly at random among the graphs where every vertex
+    /// has the degree.
+    RandomRegular {
+        vertices: usize,
+        degree: usize,
+        seed: u64,
+    },
+    /// A Barabási–Albert preferential attachment graph, where every new vertex is joined
+    /// to edges_per_vertex vertices chosen with probabilities proportional to their degrees.
+    BarabasiAlbert {
+        vertices: usize,
+        edges_per_vertex: usize,
+        seed: u64,
+    },
+    /// Random points in the unit square, joined if they're at most the radius apart.
+    RandomGeometric {
+        vertices: usize,
+        radius: f32,
+        seed: u64,
+    },
+}
+
+impl GraphGenerator {
+    /// Every family with its default parameters.
+    pub fn families() -> [GraphGenerator; 13] {
+        [
+            GraphGenerator::Grid {
+                rows: 3,
+                columns: 3,
+            },
+            GraphGenerator::Torus {
+                rows: 3,
+                columns: 3,
+            },
+            GraphGenerator::Cycle { length: 8 },
+            GraphGenerator::Complete { size: 5 },
+            GraphGenerator::CompleteBipartite { left: 3, right: 3 },
+            GraphGenerator::Hypercube { dimension: 3 },
+            GraphGenerator::Petersen,
+            GraphGenerator::Dodecahedron,
+            GraphGenerator::RandomTree { size: 10, seed: 0 },
+            GraphGenerator::ErdosRenyi {
+                vertices: 10,
+                probability: 0.3,
+                seed: 0,
+            },
+            GraphGenerator::RandomRegular {
+                vertices: 10,
+                degree: 3,
+                seed: 0,
+            },
+            GraphGenerator::BarabasiAlbert {
+                vertices: 10,
+                edges_per_vertex: 2,
+                seed: 0,
+            },
+            GraphGenerator::RandomGeometric {
+                vertices: 10,
+                radius: 0.4,
+                seed: 0,
+            },
+        ]
+    }
+
+    pub fn family_name(&self) -> &'static str {
+        match self {
+            GraphGenerator::Grid { .. } => "Grid",
+            GraphGenerator::Torus { .. } => "Torus",
+            GraphGenerator::Cycle { .. } => "Cycle",
+            GraphGenerator::Complete { .. } => "Complete",
+            GraphGenerator::CompleteBipartite { .. } => "Complete bipartite",
+            GraphGenerator::Hypercube { .. } => "Hypercube",
+            GraphGenerator::Petersen => "Petersen",
+            GraphGenerator::Dodecahedron => "Dodecahedron",
+            GraphGenerator::RandomTree { .. } => "Random tree",
+            GraphGenerator::ErdosRenyi { .. } => "Erdős–Rényi",
+            GraphGenerator::RandomRegular { .. } => "Random regular",
+            GraphGenerator::BarabasiAlbert { .. } => "Barabási–Albert",
+            GraphGenerator::RandomGeometric { .. } => "Random geometric",
+        }
+    }
+
+    /// Whether the graphs of the family are random, so they depend on the seed and
+    /// may be disconnected.
+    pub fn is_random(&self) -> bool {
+        matches!(
+            self,
+            GraphGenerator::RandomTree { .. }
+                | GraphGenerator::ErdosRenyi { .. }
+                | GraphGenerator::RandomRegular { .. }
+                | GraphGenerator::BarabasiAlbert { .. }
+                | GraphGenerator::RandomGeometric { .. }
+        )
+    }
+
+    /// The name of the generated graph, with its parameters.
+    pub fn name(&self) -> String {
+        let family_name = self.family_name();
+        match *self {
+            GraphGenerator::Grid { rows, columns } | GraphGenerator::Torus { rows, columns } => {
+                format!("{family_name} {rows}x{columns}")
+            }
+            GraphGenerator::Cycle { length } => format!("{family_name} {length}"),
+            GraphGenerator::Complete { size } => format!("{family_name} {size}"),
+            GraphGenerator::CompleteBipartite { left, right } => {
+                format!("{family_name} {left}x{right}")
+            }
+            GraphGenerator::Hypercube { dimension } => format!("{family_name} {dimension}"),
+            GraphGenerator::Petersen | GraphGenerator::Dodecahedron => family_name.to_string(),
+            GraphGenerator::RandomTree { size, seed } => {
+                format!("{family_name} {size} (seed {seed})")
+            }
+            GraphGenerator::ErdosRenyi {
+                vertices,
+                probability,
+                seed,
+            } => format!("{family_name} {vertices}, p = {probability:.2} (seed {seed})"),
+            GraphGenerator::RandomRegular {
+                vertices,
+                degree,
+                seed,
+            } => format!("{family_name} {vertices}, degree {degree} (seed {seed})"),
+            GraphGenerator::BarabasiAlbert {
+                vertices,
+                edges_per_vertex,
+                seed,
+            } => format!("{family_name} {vertices}, m = {edges_per_vertex} (seed {seed})"),
+            GraphGenerator::RandomGeometric {
+                vertices,
+                radius,
+                seed,
+            } => format!("{family_name} {vertices}, r = {radius:.2} (seed {seed})"),
+        }
+    }
+
+    /// Generates the graph. Random graphs are generated with a random number generator
+    /// seeded with their seed, so the same parameters give the same graph.
+    /// Graphs without vertices are errors, as the game can't be played on them.
+    pub fn generate(&self) -> Result<Graph, String> {
+        let name = self.name();
+        let graph = match *self {
+            GraphGenerator::Grid { rows, columns } => grid(name, rows, columns, false),
+            GraphGenerator::Torus { rows, columns } => grid(name, rows, columns, true),
+            GraphGenerator::Cycle { length } => cycle(name, length),
+            GraphGenerator::Complete { size } => complete(name, size),
+            GraphGenerator::CompleteBipartite { left, right } => {
+                complete_bipartite(name, left, right)
+            }
+            GraphGenerator::Hypercube { dimension } => hypercube(name, dimension),
+            GraphGenerator::Petersen => petersen(name),
+            GraphGenerator::Dodecahedron => dodecahedron(name),
+            GraphGenerator::RandomTree { size, seed } => {
+                random_tree(name, size, &mut GameRng::seed_from_u64(seed))
+            }
+            GraphGenerator::ErdosRenyi {
+                vertices,
+                probability,
+                seed,
+            } => erdos_renyi(
+                name,
+                vertices,
+                probability,
+                &mut GameRng::seed_from_u64(seed),
+            ),
+            GraphGenerator::RandomRegular {
+                vertices,
+                degree,
+                seed,
+            } => random_regular(name, vertices, degree, &mut GameRng::seed_from_u64(seed))?,
+            GraphGenerator::BarabasiAlbert {
+                vertices,
+                edges_per_vertex,
+                seed,
+            } => barabasi_albert(
+                name,
+                vertices,
+                edges_per_vertex,
+                &mut GameRng::seed_from_u64(seed),
+            )?,
+            GraphGenerator::RandomGeometric {
+                vertices,
+                radius,
+                seed,
+            } => random_geometric(name, vertices, radius, &mut GameRng::seed_from_u64(seed)),
+        };
+        if graph.vertices.is_empty() {
+            return Err("The graph must have at least one vertex.".to_string());
+        }
+        Ok(graph)
+    }
+}
+
+/// Scales and moves the vertices so they fit in the unit square with a margin,
+/// keeping their aspect ratio and centering them.
+pub fn normalise_vertices(vertices: &mut [(f32, f32)]) {
+    let Some(&(first_x, first_y)) = vertices.first() else {
+        return;
+    };
+    let (mut min_x, mut max_x, mut min_y, mut max_y) = (first_x, first_x, first_y, first_y);
+    for &(x, y) in vertices.iter() {
+        min_x = min_x.min(x);
+        max_x = max_x.max(x);
+        min_y = min_y.min(y);
+        max_y = max_y.max(y);
+    }
+    let extent = (max_x - min_x).max(max_y - min_y);
+    // A single vertex (or vertices on top of each other) goes to the center.
+    let scale = if extent > 0.0 {
+        (1.0 - 2.0 * MARGIN) / extent
+    } else {
+        0.0
+    };
+    let center_x = (min_x + max_x) / 2.0;
+    let center_y = (min_y + max_y) / 2.0;
+    for (x, y) in vertices.iter_mut() {
+        *x = 0.5 + (*x - center_x) * scale;
+        *y = 0.5 + (*y - center_y) * scale;
+    }
+}
+
+/// The subgraph induced by the largest connected component of the graph (the first one
+/// if several are the largest), with normalised coordinates.
+pub fn largest_connected_component(graph: &Graph) -> Graph {
+    let number_of_vertices = graph.vertices.len();
+    let mut components = vec![usize::MAX; number_of_vertices];
+    let mut component_sizes = vec![];
+    for start in 0..number_of_vertices {
+        if components[start] != usize::MAX {
+            continue;
+        }
+        let component = component_sizes.len();
+        components[start] = component;
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(v) = stack.pop() {
+            size += 1;
+            for &u in &graph.adjacency_list[v] {
+                if components[u] == usize::MAX {
+                    components[u] = component;
+                    stack.push(u);
+                }
+            }
+        }
+        component_sizes.push(size);
+    }
+    // max_by_key returns the last maximum, so we search the reversed sizes.
+    let Some(largest) = (0..component_sizes.len())
+        .rev()
+        .max_by_key(|&component| component_sizes[component])
+    else {
+        return graph.clone();
+    };
+
+    // new_indices[v] is the index of v in the component.
+    let mut new_indices = vec![usize::MAX; number_of_vertices];
+    let kept: Vec<_> = (0..number_of_vertices)
+        .filter(|&v| components[v] == largest)
+        .collect();
+    for (new_index, &v) in kept.iter().enumerate() {
+        new_indices[v] = new_index;
+    }
+    let new_indices = &new_indices;
+    let edges = kept.iter().flat_map(|&v| {
+        graph.adjacency_list[v]
+            .iter()
+            .map(move |&u| (new_indices[v], new_indices[u]))
+    });
+    graph_from_edges(
+        graph.name.clone(),
+        kept.iter().map(|&v| graph.vertices[v]).collect(),
+        edges,
+    )
+}
+
+// Builds a graph from its vertex coordinates and edges, and normalises the coordinates.
+// Loops and repeated edges are left out.
+pub(crate) fn graph_from_edges(
+    name: String,
+    mut vertices: Vec<(f32, f32)>,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Graph {
+    let adjacency_list = adjacency_list_from_edges(vertices.len(), edges);
+    normalise_vertices(&mut vertices);
+    Graph {
+        name,
+        vertices,
+        adjacency_list,
+    }
+}
+
+// The symmetric adjacency list of the edges, without loops and repeated edges.
+pub(crate) fn adjacency_list_from_edges(
+    number_of_vertices: usize,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> Vec<Vec<usize>> {
+    let mut adjacency_list = vec![vec![]; number_of_vertices];
+    for (v, u) in edges {
+        if v != u && !adjacency_list[v].contains(&u) {
+            adjacency_list[v].push(u);
+            adjacency_list[u].push(v);
+        }
+    }
+    adjacency_list
+}
+
+// The positions of points evenly spaced on a circle, starting at the top.
+pub(crate) fn circle_points(
+    number_of_points: usize,
+    radius: f32,
+    angle_offset: f32,
+) -> Vec<(f32, f32)> {
+    (0..number_of_points)
+        .map(|i| {
+            let angle = TAU * i as f32 / number_of_points as f32 - FRAC_PI_2 + angle_offset;
+            (radius * angle.cos(), radius * angle.sin())
+        })
+        .collect()
+}
+
+// A grid, whose rows and columns wrap around if it's a torus.
+fn grid(name: String, rows: usize, columns: usize, torus: bool) -> Graph {
+    let index = |row: usize, column: usize| row * columns + column;
+    let vertices = (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (column as f32, row as f32)))
+        .collect();
+    let mut edges = vec![];
+    for row in 0..rows {
+        for column in 0..columns {
+            if column + 1 < columns || torus {
+                edges.push((index(row, column), index(row, (column + 1) % columns)));
+            }
+            if row + 1 < rows || torus {
+                edges.push((index(row, column), index((row + 1) % rows, column)));
+            }
+        }
+    }
+    graph_from_edges(name, vertices, edges)
+}
+
+fn cycle(name: String, length: usize) -> Graph {
+    let edges = (0..length).map(|v| (v, (v + 1) % length));
+    graph_from_edges(name, circle_points(length, 1.0, 0.0), edges)
+}
+
+fn complete(name: String, size: usize) -> Graph {
+    let edges = (0..size).flat_map(|v| (v + 1..size).map(move |u| (v, u)));
+    graph_from_edges(name, circle_points(size, 1.0, 0.0), edges)
+}
+
+// The two sides are drawn as two columns.
+fn complete_bipartite(name: String, left: usize, right: usize) -> Graph {
+    let height = left.max(right) as f32;
+    let column = move |x: f32, size: usize| {
+        (0..size).map(move |i| (x, (i as f32 + 0.5) * height / size as f32))
+    };
+    let vertices = column(0.0, left)
+        .chain(column((height / 2.0).max(1.0), right))
+        .collect();
+    let edges = (0..left).flat_map(|v| (0..right).map(move |u| (v, left + u)));
+    graph_from_edges(name, vertices, edges)
+}
+
+// The vertices are the bit strings of the dimension, adjacent if they differ in one bit.
+// Each bit moves a vertex along its own direction, which draws the 3-cube as a cube.
+// The directions have different lengths, so no two vertices are drawn on top of each other.
+fn hypercube(name: String, dimension: usize) -> Graph {
+    let directions: Vec<_> = (0..dimension)
+        .map(|bit| {
+            let angle = PI * bit as f32 / dimension as f32;
+            let length = 1.0 + 0.1 * bit as f32;
+            (length * angle.cos(), length * angle.sin())
+        })
+        .collect();
+    let vertices = (0..1 << dimension)
+        .map(|v: usize| {
+            directions
+                .iter()
+                .enumerate()
+                .filter(|&(bit, _)| v & (1 << bit) != 0)
+                .fold((0.0, 0.0), |(x, y), (_, (dx, dy))| (x + dx, y + dy))
+        })
+        .collect();
+    let edges = (0..1 << dimension)
+        .flat_map(|v: usize| (0..dimension).map(move |bit| (v, v ^ (1 << bit))))
+        .filter(|&(v, u)| v < u);
+    graph_from_edges(name, vertices, edges)
+}
+
+// An outer pentagon joined to an inner pentagram.
+fn petersen(name: String) -> Graph {
+    let vertices = circle_points(5, 1.0, 0.0)
+        .into_iter()
+        .chain(circle_points(5, 0.5, 0.0))
+        .collect();
+    let edges = (0..5).flat_map(|i| [(i, (i + 1) % 5), (i, 5 + i), (5 + i, 5 + (i + 2) % 5)]);
+    graph_from_edges(name, vertices, edges)
+}
+
+// Drawn as four rings of five vertices: an outer pentagon, two middle rings which
+// alternate around a cycle of ten, and an inner pentagon.
+fn dodecahedron(name: String) -> Graph {
+    let rotation = PI / 5.0;
+    let vertices = circle_points(5, 1.0, 0.0)
+        .into_iter()
+        .chain(circle_points(5, 0.7, 0.0))
+        .chain(circle_points(5, 0.55, rotation))
+        .chain(circle_points(5, 0.3, rotation))
+        .collect();
+    let edges = (0..5).flat_map(|i| {
+        let next = (i + 1) % 5;
+        [
+            (i, next),
+            (i, 5 + i),
+            (5 + i, 10 + i),
+            (10 + i, 5 + next),
+            (10 + i, 15 + i),
+            (15 + i, 15 + next),
+        ]
+    });
+    graph_from_edges(name, vertices, edges)
+}
+
+// A uniformly random labelled tree, decoded from a random Prüfer sequence,
+// drawn with the root (vertex 0) at the top and the children below their parent.
+pub(crate) fn random_tree(name: String, size: usize, rng: &mut GameRng) -> Graph {
+    let mut edges = vec![];
+    if size == 2 {
+        edges.push((0, 1));
+    } else if size > 2 {
+        let sequence: Vec<usize> = (0..size - 2).map(|_| rng.gen_range(0..size)).collect();
+        let mut degrees = vec![1; size];
+        for &v in &sequence {
+            degrees[v] += 1;
+        }
+        for &v in &sequence {
+            // We join v to the smallest leaf.
+            let leaf = (0..size).find(|&u| degrees[u] == 1).unwrap();
+            edges.push((leaf, v));
+            degrees[leaf] -= 1;
+            degrees[v] -= 1;
+        }
+        let mut last = (0..size).filter(|&u| degrees[u] == 1);
+        // Two vertices are left, which we join.
+        edges.push((last.next().unwrap(), last.next().unwrap()));
+    }
+
+    let mut tree = graph_from_edges(name, vec![(0.0, 0.0); size], edges);
+    tree.vertices = tree_layout(&tree);
+    normalise_vertices(&mut tree.vertices);
+    tree
+}
+
+// Places every vertex of a tree at the depth of its distance to vertex 0, and in the middle
+// of the horizontal interval of its subtree, whose width is its number of leaves.
+fn tree_layout(tree: &Graph) -> Vec<(f32, f32)> {
+    let size = tree.vertices.len();
+    if size == 0 {
+        return vec![];
+    }
+    // The vertices in BFS order from the root, with their parents and depths.
+    let mut order = vec![0];
+    let mut parents = vec![usize::MAX; size];
+    let mut depths = vec![0; size];
+    parents[0] = 0;
+    let mut next = 0;
+    while next < order.len() {
+        let v = order[next];
+        next += 1;
+        for &u in &tree.adjacency_list[v] {
+            if parents[u] == usize::MAX {
+                parents[u] = v;
+                depths[u] = depths[v] + 1;
+                order.push(u);
+            }
+        }
+    }
+    let is_child = |v: usize, u: usize| u != 0 && parents[u] == v;
+
+    let mut leaves = vec![0; size];
+    for &v in order.iter().rev() {
+        leaves[v] = tree.adjacency_list[v]
+            .iter()
+            .filter(|&&u| is_child(v, u))
+            .map(|&u| leaves[u])
+            .sum::<usize>()
+            .max(1);
+    }
+
+    // starts[v] is the left end of the interval of v's subtree.
+    let mut starts = vec![0; size];
+    let mut vertices = vec![(0.0, 0.0); size];
+    for &v in &order {
+        let mut start = starts[v];
+        for &u in &tree.adjacency_list[v] {
+            if is_child(v, u) {
+                starts[u] = start;
+                start += leaves[u];
+            }
+        }
+        vertices[v] = (
+            starts[v] as f32 + leaves[v] as f32 / 2.0,
+            depths[v] as f32 * 1.5,
+        );
+    }
+    vertices
+}
+
+// Random graphs without a natural drawing are laid out by the force-directed layout,
+// starting from a circle.
+fn erdos_renyi(name: String, vertices: usize, probability: f32, rng: &mut GameRng) -> Graph {
+    let probability = probability.clamp(0.0, 1.0) as f64;
+    let mut edges = vec![];
+    for v in 0..vertices {
+        for u in v + 1..vertices {
+            if rng.gen_bool(probability) {
+                edges.push((v, u));
+            }
+        }
+    }
+    let mut graph = graph_from_edges(name, circle_points(vertices, 1.0, 0.0), edges);
+    force_directed(&mut graph);
+    graph
+}
+
+// Pairs the stubs (degree copies of every vertex) at random, never joining a vertex to itself
+// or joining two vertices twice, and starts again if no pair of the remaining stubs can be
+// joined. This is the algorithm of Steger and Wormald, which is close to uniform.
+fn random_regular(
+    name: String,
+    vertices: usize,
+    degree: usize,
+    rng: &mut GameRng,
+) -> Result<Graph, String> {
+    if vertices > 0 && degree >= vertices {
+        return Err("The degree must be smaller than the number of vertices.".to_string());
+    }
+    if vertices * degree % 2 != 0 {
+        return Err("The number of vertices times the degree must be even.".to_string());
+    }
+
+    for _ in 0..MAX_REGULAR_ATTEMPTS {
+        let mut stubs: Vec<usize> = (0..vertices)
+            .flat_map(|v| std::iter::repeat(v).take(degree))
+            .collect();
+        let mut adjacency_list = vec![vec![]; vertices];
+        let can_join = |adjacency_list: &Vec<Vec<usize>>, v: usize, u: usize| {
+            v != u && !adjacency_list[v].contains(&u)
+        };
+
+        while !stubs.is_empty() {
+            let mut pair = None;
+            for _ in 0..MAX_PAIRING_TRIES {
+                let (i, j) = (rng.gen_range(0..stubs.len()), rng.gen_range(0..stubs.len()));
+                if can_join(&adjacency_list, stubs[i], stubs[j]) {
+                    pair = Some((i, j));
+                    break;
+                }
+            }
+            if pair.is_none() {
+                // Few pairs can be joined, so we look for them all.
+                let pairs: Vec<_> = (0..stubs.len())
+                    .flat_map(|i| (i + 1..stubs.len()).map(move |j| (i, j)))
+                    .filter(|&(i, j)| can_join(&adjacency_list, stubs[i], stubs[j]))
+                    .collect();
+                pair = pairs.choose(rng).copied();
+            }
+            let Some((i, j)) = pair else {
+                break;
+            };
+            let (v, u) = (stubs[i], stubs[j]);
+            adjacency_list[v].push(u);
+            adjacency_list[u].push(v);
+            // We remove the larger index first, so the smaller one stays valid.
+            stubs.swap_remove(i.max(j));
+            stubs.swap_remove(i.min(j));
+        }
+
+        if stubs.is_empty() {
+            let edges = adjacency_list
+                .iter()
+                .enumerate()
+                .flat_map(|(v, neighbours)| neighbours.iter().map(move |&u| (v, u)));
+            let mut graph = graph_from_edges(
+                name,
+                circle_points(vertices, 1.0, 0.0),
+                edges.collect::<Vec<_>>(),
+            );
+            force_directed(&mut graph);
+            return Ok(graph);
+        }
+    }
+    Err("Couldn't generate the random regular graph, try another seed.".to_string())
+}
+
+// Starts with edges_per_vertex vertices without edges. Every new vertex is joined to
+// edges_per_vertex distinct vertices, chosen from a list where every vertex appears
+// once for every edge it has (and the first vertices once each, for the first new vertex).
+fn barabasi_albert(
+    name: String,
+    vertices: usize,
+    edges_per_vertex: usize,
+    rng: &mut GameRng,
+) -> Result<Graph, String> {
+    if edges_per_vertex == 0 || edges_per_vertex >= vertices {
+        return Err(
+            "The number of edges of every new vertex must be at least 1 and smaller than \
+             the number of vertices."
+                .to_string(),
+        );
+    }
+
+    let mut edges = vec![];
+    let mut targets: Vec<usize> = (0..edges_per_vertex).collect();
+    let mut repeated_vertices = vec![];
+    for source in edges_per_vertex..vertices {
+        for &target in &targets {
+            edges.push((source, target));
+        }
+        repeated_vertices.extend_from_slice(&targets);
+        repeated_vertices.extend(std::iter::repeat(source).take(edges_per_vertex));
+
+        targets.clear();
+        while targets.len() < edges_per_vertex {
+            let target = *repeated_vertices.choose(rng).unwrap();
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    let mut graph = graph_from_edges(name, circle_points(vertices, 1.0, 0.0), edges);
+    force_directed(&mut graph);
+    Ok(graph)
+}
+
+// The random points are also the drawing of the graph.
+fn random_geometric(name: String, vertices: usize, radius: f32, rng: &mut GameRng) -> Graph {
+    let points: Vec<(f32, f32)> = (0..vertices)
+        .map(|_| (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0)))
+        .collect();
+    let mut edges = vec![];
+    for v in 0..vertices {
+        for u in v + 1..vertices {
+            let (dx, dy) = (points[v].0 - points[u].0, points[v].1 - points[u].1);
+            if dx * dx + dy * dy <= radius * radius {
+                edges.push((v, u));
+            }
+        }
+    }
+    graph_from_edges(name, points, edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number_of_edges(graph: &Graph) -> usize {
+        graph.adjacency_list.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    fn is_connected(graph: &Graph) -> bool {
+        let mut visited = vec![false; graph.vertices.len()];
+        let mut stack = vec![0];
+        visited[0] = true;
+        while let Some(v) = stack.pop() {
+            for &u in &graph.adjacency_list[v] {
+                if !visited[u] {
+                    visited[u] = true;
+                    stack.push(u);
+                }
+            }
+        }
+        visited.into_iter().all(|visited| visited)
+    }
+
+    #[test]
+    fn families_have_the_right_sizes() {
+        let sizes: Vec<_> = GraphGenerator::families()
+            .iter()
+            .map(|generator| {
+                let graph = generator.generate().unwrap();
+                assert_eq!(graph.name, generator.name());
+                assert_eq!(graph.adjacency_list.len(), graph.vertices.len());
+                assert!(graph
+                    .vertices
+                    .iter()
+                    .all(|&(x, y)| (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y)));
+                (graph.vertices.len(), number_of_edges(&graph))
+            })
+            .collect();
+        assert_eq!(
+            sizes,
+            vec![
+                (9, 12),
+                (9, 18),
+                (8, 8),
+                (5, 10),
+                (6, 9),
+                (8, 12),
+                (10, 15),
+                (20, 30),
+                (10, 9),
+                (10, sizes[9].1),
+                (10, 15),
+                (10, 16),
+                (10, sizes[12].1),
+            ]
+        );
+    }
+
+    #[test]
+    fn cubic_families_are_cubic() {
+        for generator in [
+            GraphGenerator::Hypercube { dimension: 3 },
+            GraphGenerator::Petersen,
+            GraphGenerator::Dodecahedron,
+        ] {
+            let graph = generator.generate().unwrap();
+            assert!(graph
+                .adjacency_list
+                .iter()
+                .all(|neighbours| neighbours.len() == 3));
+            assert!(is_connected(&graph));
+        }
+    }
+
+    #[test]
+    fn random_trees_are_trees() {
+        for size in 1..20 {
+            for seed in 0..5 {
+                let generator = GraphGenerator::RandomTree { size, seed };
+                let tree = generator.generate().unwrap();
+                assert_eq!(tree.vertices.len(), size);
+                assert_eq!(number_of_edges(&tree), size - 1);
+                assert!(is_connected(&tree));
+                assert_eq!(
+                    generator.generate().unwrap().adjacency_list,
+                    tree.adjacency_list
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_graphs_depend_only_on_their_seed() {
+        for generator in &GraphGenerator::families()[9..] {
+            let graph = generator.generate().unwrap();
+            assert_eq!(
+                generator.generate().unwrap().adjacency_list,
+                graph.adjacency_list
+            );
+        }
+        let graphs: Vec<_> = (0..5)
+            .map(|seed| {
+                GraphGenerator::ErdosRenyi {
+                    vertices: 10,
+                    probability: 0.5,
+                    seed,
+                }
+                .generate()
+                .unwrap()
+                .adjacency_list
+            })
+            .collect();
+        assert!(graphs.iter().any(|graph| *graph != graphs[0]));
+    }
+
+    #[test]
+    fn random_graphs_have_the_right_sizes() {
+        let complete = GraphGenerator::ErdosRenyi {
+            vertices: 6,
+            probability: 1.0,
+            seed: 0,
+        };
+        assert_eq!(number_of_edges(&complete.generate().unwrap()), 15);
+        let empty = GraphGenerator::RandomGeometric {
+            vertices: 6,
+            radius: 0.0,
+            seed: 0,
+        };
+        assert_eq!(number_of_edges(&empty.generate().unwrap()), 0);
+
+        for seed in 0..10 {
+            let regular = GraphGenerator::RandomRegular {
+                vertices: 12,
+                degree: 4,
+                seed,
+            };
+            let graph = regular.generate().unwrap();
+            assert!(graph
+                .adjacency_list
+                .iter()
+                .all(|neighbours| neighbours.len() == 4));
+
+            let barabasi_albert = GraphGenerator::BarabasiAlbert {
+                vertices: 12,
+                edges_per_vertex: 3,
+                seed,
+            };
+            let graph = barabasi_albert.generate().unwrap();
+            assert_eq!(number_of_edges(&graph), 27);
+            assert!(is_connected(&graph));
+        }
+    }
+
+    #[test]
+    fn impossible_random_graphs_are_errors() {
+        for generator in [
+            GraphGenerator::RandomRegular {
+                vertices: 5,
+                degree: 3,
+                seed: 0,
+            },
+            GraphGenerator::RandomRegular {
+                vertices: 4,
+                degree: 4,
+                seed: 0,
+            },
+            GraphGenerator::BarabasiAlbert {
+                vertices: 3,
+                edges_per_vertex: 3,
+                seed: 0,
+            },
+        ] {
+            assert!(generator.generate().is_err());
+        }
+    }
+
+    #[test]
+    fn graphs_without_vertices_are_errors() {
+        for generator in [
+            GraphGenerator::Grid {
+                rows: 0,
+                columns: 3,
+            },
+            GraphGenerator::Cycle { length: 0 },
+            GraphGenerator::Complete { size: 0 },
+            GraphGenerator::RandomTree { size: 0, seed: 0 },
+            GraphGenerator::ErdosRenyi {
+                vertices: 0,
+                probability: 0.5,
+                seed: 0,
+            },
+            GraphGenerator::RandomGeometric {
+                vertices: 0,
+                radius: 0.5,
+                seed: 0,
+            },
+        ] {
+            assert!(generator.generate().is_err());
+        }
+    }
+
+    #[test]
+    fn keeps_the_largest_connected_component() {
+        // A triangle and a path of four vertices.
+        let graph = graph_from_edges(
+            "Two components".to_string(),
+            (0..7).map(|v| (v as f32, 0.0)).collect(),
+            [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 6)],
+        );
+        let component = largest_connected_component(&graph);
+        assert_eq!(component.name, graph.name);
+        assert_eq!(
+            component.adjacency_list,
+            vec![vec![1], vec![0, 2], vec![1, 3], vec![2]]
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{
+    game::{CopPositions, CopStrategy, GameRng, Graph, RobberPosition, RobberStrategy},
+    optimal::choose_best,
+};
+use std::{any::Any, collections::VecDeque};
+
+/// The shortest path distances between all pairs of vertices, computed by a BFS from every vertex.
+pub struct Distances {
+    number_of_vertices: usize,
+    distances: Vec<u32>,
+}
+
+impl Distances {
+    // The distance between vertices in different components.
+    const UNREACHABLE: u32 = u32::MAX;
+
+    pub fn new(graph: &Graph) -> Distances {
+        let number_of_vertices = graph.vertices.len();
+        let mut distances = vec![Self::UNREACHABLE; number_of_vertices * number_of_vertices];
+
+        let mut queue = VecDeque::new();
+        for source in 0..number_of_vertices {
+            let row =
+                &mut distances[source * number_of_vertices..(source + 1) * number_of_vertices];
+            row[source] = 0;
+            queue.push_back(source);
+            while let Some(vertex) = queue.pop_front() {
+                for &neighbour in &graph.adjacency_list[vertex] {
+                    if row[neighbour] == Self::UNREACHABLE {
+                        row[neighbour] = row[vertex] + 1;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        Distances {
+            number_of_vertices,
+            distances,
+        }
+    }
+
+    /// The length of a shortest path from u to v, or None if there is no path.
+    pub fn get(&self, u: usize, v: usize) -> Option<u32> {
+        let distance = self.distances[u * self.number_of_vertices + v];
+        (distance != Self::UNREACHABLE).then_some(distance)
+    }
+
+    // Like get, but unreachable vertices are further away than any reachable vertex.
+    fn get_or_max(&self, u: usize, v: usize) -> u32 {
+        self.distances[u * self.number_of_vertices + v]
+    }
+}
+
+/// A cop which moves each cop along a shortest path towards the robber.
+/// The cops move one after the other, and a cop doesn't move onto a vertex
+/// another cop has already taken unless it captures the robber there.
+pub struct ChaserCop {
+    number_of_cops: u8,
+    distances: Option<Distances>,
+}
+
+impl ChaserCop {
+    pub fn new(number_of_cops: u8) -> Self {
+        Self {
+            number_of_cops,
+            distances: None,
+        }
+    }
+}
+
+impl CopStrategy for ChaserCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        let distances = self.distances.get_or_insert_with(|| Distances::new(graph));
+
+        // The first cop starts at a center of the graph, and every other cop
+        // starts as far as possible from the cops already placed.
+        let mut cop_positions: CopPositions = vec![];
+        for _ in 0..self.number_of_cops {
+            let options = (0..graph.vertices.len())
+                .map(|vertex| {
+                    let value = if cop_positions.is_empty() {
+                        // The eccentricity of the vertex.
+                        (0..graph.vertices.len())
+                            .map(|other| distances.get_or_max(vertex, other))
+                            .max()
+                            .unwrap_or(0)
+                    } else {
+                        cop_positions
+                            .iter()
+                            .map(|&cop_position| distances.get_or_max(cop_position, vertex))
+                            .min()
+                            .unwrap_or(0)
+                    };
+                    (vertex, value)
+                })
+                .collect();
+            cop_positions.push(choose_best(options, cop_positions.is_empty(), rng));
+        }
+        cop_positions
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        let distances = self.distances.get_or_insert_with(|| Distances::new(graph));
+
+        let mut new_cop_positions: CopPositions = vec![];
+        for &cop_position in cop_positions {
+            // The cop can stay in place or move to a neighbour. It doesn't collapse onto
+            // a cop which has already moved, unless it captures the robber.
+            let options: Vec<_> = graph.adjacency_list[cop_position]
+                .iter()
+                .chain(std::iter::once(&cop_position))
+                .filter(|&&vertex| {
+                    vertex == robber_position || !new_cop_positions.contains(&vertex)
+                })
+                .map(|&vertex| (vertex, distances.get_or_max(vertex, robber_position)))
+                .collect();
+            // If every move is taken, the cop stays in place.
+            new_cop_positions.push(if options.is_empty() {
+                cop_position
+            } else {
+                choose_best(options, true, rng)
+            });
+        }
+        new_cop_positions
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// How the evasive robber chooses between moves which are equally far from the nearest cop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TieBreaking {
+    /// Choose uniformly at random.
+    Random,
+    /// Prefer the largest total distance to all the cops.
+    TotalDistance,
+    /// Prefer the vertex with the most neighbours, so the robber has more ways to escape.
+    MostNeighbours,
+}
+
+impl TieBreaking {
+    pub const ALL: [TieBreaking; 3] = [
+        TieBreaking::Random,
+        TieBreaking::TotalDistance,
+        TieBreaking::MostNeighbours,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TieBreaking::Random => "Random",
+            TieBreaking::TotalDistance => "Total distance",
+            TieBreaking::MostNeighbours => "Most neighbours",
+        }
+    }
+}
+
+/// The parameters of the evasive robber.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct EvasiveSettings {
+    pub tie_breaking: TieBreaking,
+}
+
+impl Default for EvasiveSettings {
+    fn default() -> Self {
+        Self {
+            tie_breaking: TieBreaking::Random,
+        }
+    }
+}
+
+/// A robber which moves to the vertex furthest from the nearest cop.
+pub struct EvasiveRobber {
+    settings: EvasiveSettings,
+    distances: Option<Distances>,
+}
+
+impl EvasiveRobber {
+    pub fn new(settings: EvasiveSettings) -> Self {
+        Self {
+            settings,
+            distances: None,
+        }
+    }
+
+    pub fn settings(&self) -> &EvasiveSettings {
+        &self.settings
+    }
+
+    // Chooses the vertex maximising the distance to the nearest cop, breaking ties with the settings.
+    fn choose(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        options: impl Iterator<Item = usize>,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let distances = self.distances.get_or_insert_with(|| Distances::new(graph));
+        let nearest_cop = |vertex: usize| {
+            cop_positions
+                .iter()
+                .map(|&cop_position| distances.get_or_max(cop_position, vertex))
+                .min()
+                .unwrap_or(u32::MAX)
+        };
+        let options: Vec<_> = options
+            .map(|vertex| (vertex, nearest_cop(vertex)))
+            .collect();
+        let best = options.iter().map(|(_, value)| *value).max();
+
+        let tied = options
+            .into_iter()
+            .filter(|(_, value)| Some(*value) == best)
+            .map(|(vertex, _)| {
+                let value = match self.settings.tie_breaking {
+                    TieBreaking::Random => 0,
+                    TieBreaking::TotalDistance => cop_positions
+                        .iter()
+                        .map(|&cop_position| distances.get_or_max(cop_position, vertex) as u64)
+                        .sum::<u64>()
+                        .min(u32::MAX as u64)
+                        as u32,
+                    TieBreaking::MostNeighbours => graph.adjacency_list[vertex].len() as u32,
+                };
+                (vertex, value)
+            })
+            .collect();
+        choose_best(tied, false, rng)
+    }
+}
+
+impl RobberStrategy for EvasiveRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.choose(graph, cop_positions, 0..graph.vertices.len(), rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let options = graph.adjacency_list[robber_position]
+            .iter()
+            .copied()
+            .chain(std::iter::once(robber_position));
+        self.choose(graph, cop_positions, options, rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{template_graphs, Game, RandomRobber};
+    use rand::SeedableRng;
+
+    #[test]
+    fn distances_on_a_path() {
+        let mut graph = template_graphs()[1].clone();
+        // A separate vertex which can't be reached.
+        graph.vertices.push((0.9, 0.5));
+        graph.adjacency_list.push(vec![]);
+        let distances = Distances::new(&graph);
+        assert_eq!(distances.get(0, 4), Some(4));
+        assert_eq!(distances.get(3, 1), Some(2));
+        assert_eq!(distances.get(2, 2), Some(0));
+        assert_eq!(distances.get(0, 5), None);
+    }
+
+    #[test]
+    fn chaser_starts_at_the_center_and_moves_towards_the_robber() {
+        let graph = &template_graphs()[1];
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut cop = ChaserCop::new(1);
+        assert_eq!(cop.start(graph, &mut rng), vec![2]);
+        assert_eq!(cop.step(graph, &vec![0], 4, &mut rng), vec![1]);
+        assert_eq!(cop.step(graph, &vec![3], 4, &mut rng), vec![4]);
+    }
+
+    #[test]
+    fn chaser_catches_the_robber_on_a_path() {
+        let mut game = Game::new(
+            &template_graphs()[1],
+            2,
+            Box::new(ChaserCop::new(1)),
+            Box::new(RandomRobber::new()),
+            0,
+        );
+        while game.score[0] + game.score[1] < 20 {
+            game.update();
+        }
+        assert_eq!(game.score, [20, 0]);
+    }
+
+    #[test]
+    fn evasive_robber_keeps_away_from_the_cop() {
+        let graph = &template_graphs()[1];
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut robber = EvasiveRobber::new(EvasiveSettings::default());
+        assert_eq!(robber.start(graph, &vec![0], &mut rng), 4);
+        assert_eq!(robber.step(graph, &vec![2], 3, &mut rng), 4);
+        assert_eq!(robber.step(graph, &vec![4], 3, &mut rng), 2);
+    }
+
+    #[test]
+    fn evasive_robber_breaks_ties() {
+        // Path5 with a triangle 3-5-6, so 5 and 6 are as far from the cop at the center
+        // as the ends of the path, but have more neighbours.
+        let mut graph = template_graphs()[1].clone();
+        graph.vertices.extend([(0.7, 0.9), (0.9, 0.9)]);
+        graph.adjacency_list[3].extend([5, 6]);
+        graph.adjacency_list.push(vec![3, 6]);
+        graph.adjacency_list.push(vec![3, 5]);
+
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut robber = EvasiveRobber::new(EvasiveSettings {
+            tie_breaking: TieBreaking::MostNeighbours,
+        });
+        for _ in 0..20 {
+            let start = robber.start(&graph, &vec![2], &mut rng);
+            assert!([5, 6].contains(&start));
+        }
+
+        let mut robber = EvasiveRobber::new(EvasiveSettings::default());
+        for _ in 0..20 {
+            let start = robber.start(&graph, &vec![2], &mut rng);
+            assert!([0, 4, 5, 6].contains(&start));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::game::{CopPositions, CopStrategy, GameRng, Graph, RobberPosition, RobberStrategy};
+use std::any::Any;
+
+/// Cops controlled by the user. The app sets the next move with set_next_move,
+/// and the game waits until it's set before playing the cops' turn.
+/// If the game plays without a move, the cops stay in place.
+pub struct HumanCop {
+    number_of_cops: u8,
+    next_move: Option<CopPositions>,
+}
+
+impl HumanCop {
+    pub fn new(number_of_cops: u8) -> Self {
+        Self {
+            number_of_cops,
+            next_move: None,
+        }
+    }
+
+    pub fn has_next_move(&self) -> bool {
+        self.next_move.is_some()
+    }
+
+    pub fn set_next_move(&mut self, cop_positions: CopPositions) {
+        self.next_move = Some(cop_positions);
+    }
+}
+
+impl CopStrategy for HumanCop {
+    fn start(&mut self, _graph: &Graph, _rng: &mut GameRng) -> CopPositions {
+        self.next_move
+            .take()
+            .unwrap_or_else(|| vec![0; self.number_of_cops as usize])
+    }
+
+    fn step(
+        &mut self,
+        _graph: &Graph,
+        cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+        _rng: &mut GameRng,
+    ) -> CopPositions {
+        self.next_move
+            .take()
+            .unwrap_or_else(|| cop_positions.clone())
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+        self.next_move = None;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A robber controlled by the user, see [`HumanCop`].
+/// If the game plays without a move, the robber stays in place.
+#[derive(Default)]
+pub struct HumanRobber {
+    next_move: Option<RobberPosition>,
+}
+
+impl HumanRobber {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn has_next_move(&self) -> bool {
+        self.next_move.is_some()
+    }
+
+    pub fn set_next_move(&mut self, robber_position: RobberPosition) {
+        self.next_move = Some(robber_position);
+    }
+}
+
+impl RobberStrategy for HumanRobber {
+    fn start(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.next_move.take().unwrap_or(0)
+    }
+
+    fn step(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        _rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.next_move.take().unwrap_or(robber_position)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+        self.next_move = None;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
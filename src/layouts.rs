@@ -0,0 +1,348 @@
+use crate::{
+    game::Graph,
+    generators::{circle_points, normalise_vertices},
+};
+
+// The number of times the Fruchterman-Reingold layout moves the vertices.
+const FORCE_DIRECTED_ITERATIONS: usize = 300;
+// The furthest a vertex moves in the first iteration of the Fruchterman-Reingold layout.
+// The limit cools down to 0 over the iterations.
+const INITIAL_TEMPERATURE: f32 = 0.1;
+// Vertices closer than this are pushed apart in a fixed direction, as they have no direction
+// between them.
+const MIN_DISTANCE: f32 = 1e-4;
+// The number of power iterations for each eigenvector of the spectral layout.
+const SPECTRAL_ITERATIONS: usize = 2000;
+
+/// An algorithm which lays out a graph by rewriting its vertices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    ForceDirected,
+    Circular,
+    Spectral,
+    GridSnap,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 4] = [
+        Layout::ForceDirected,
+        Layout::Circular,
+        Layout::Spectral,
+        Layout::GridSnap,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::ForceDirected => "Force-directed",
+            Layout::Circular => "Circular",
+            Layout::Spectral => "Spectral",
+            Layout::GridSnap => "Snap to grid",
+        }
+    }
+
+    /// Lays out the graph, leaving its vertices normalised.
+    pub fn apply(self, graph: &mut Graph) {
+        match self {
+            Layout::ForceDirected => force_directed(graph),
+            Layout::Circular => circular(graph),
+            Layout::Spectral => spectral(graph),
+            Layout::GridSnap => snap_to_grid(graph),
+        }
+    }
+}
+
+/// The Fruchterman-Reingold layout: every pair of vertices repels each other and every edge
+/// pulls its ends together, and the vertices move less and less until they settle.
+/// Starts from the current vertices, so it also untangles a drawing which is nearly right.
+/// The layout is deterministic.
+pub fn force_directed(graph: &mut Graph) {
+    let vertices = &mut graph.vertices;
+    let number_of_vertices = vertices.len();
+    normalise_vertices(vertices);
+    if number_of_vertices < 2 {
+        return;
+    }
+
+    // The ideal distance between the vertices if they filled the unit square.
+    let k = (1.0 / number_of_vertices as f32).sqrt();
+    let mut displacements = vec![(0.0, 0.0); number_of_vertices];
+    for iteration in 0..FORCE_DIRECTED_ITERATIONS {
+        displacements.fill((0.0, 0.0));
+        let mut add_force = |v: usize, u: usize, force: fn(f32) -> f32| {
+            let (mut dx, mut dy) = (vertices[v].0 - vertices[u].0, vertices[v].1 - vertices[u].1);
+            let mut distance = (dx * dx + dy * dy).sqrt();
+            if distance < MIN_DISTANCE {
+                let angle = (v * number_of_vertices + u) as f32;
+                (dx, dy, distance) = (
+                    MIN_DISTANCE * angle.cos(),
+                    MIN_DISTANCE * angle.sin(),
+                    MIN_DISTANCE,
+                );
+            }
+            // A positive force pushes v away from u.
+            let force = force(distance / k);
+            let (x, y) = (dx / distance * force, dy / distance * force);
+            displacements[v].0 += x;
+            displacements[v].1 += y;
+            displacements[u].0 -= x;
+            displacements[u].1 -= y;
+        };
+        // The forces are k^2 / distance and -distance^2 / k, divided by k.
+        for v in 0..number_of_vertices {
+            for u in v + 1..number_of_vertices {
+                add_force(v, u, |distance| 1.0 / distance);
+            }
+        }
+        for (v, neighbours) in graph.adjacency_list.iter().enumerate() {
+            for &u in neighbours.iter().filter(|&&u| u > v) {
+                add_force(v, u, |distance| -distance * distance);
+            }
+        }
+
+        let temperature =
+            INITIAL_TEMPERATURE * (1.0 - iteration as f32 / FORCE_DIRECTED_ITERATIONS as f32);
+        for (vertex, &(x, y)) in vertices.iter_mut().zip(&displacements) {
+            let length = (x * x + y * y).sqrt();
+            if length > 0.0 {
+                let step = length.min(temperature) / length;
+                vertex.0 = (vertex.0 + x * step).clamp(0.0, 1.0);
+                vertex.1 = (vertex.1 + y * step).clamp(0.0, 1.0);
+            }
+        }
+    }
+    normalise_vertices(vertices);
+}
+
+/// Places the vertices evenly on a circle in the order of their indices.
+pub fn circular(graph: &mut Graph) {
+    graph.vertices = circle_points(graph.vertices.len(), 1.0, 0.0);
+    normalise_vertices(&mut graph.vertices);
+}
+
+/// Uses the eigenvectors of the two smallest non-zero eigenvalues of the Laplacian of
+/// the graph as the coordinates of the vertices, which draws many symmetric graphs
+/// (like grids and cycles) exactly. The components of a disconnected graph are drawn on top
+/// of each other, as every component has its own zero eigenvalue.
+pub fn spectral(graph: &mut Graph) {
+    let number_of_vertices = graph.vertices.len();
+    if number_of_vertices < 3 {
+        circular(graph);
+        return;
+    }
+
+    // We find the eigenvectors of the largest eigenvalues of shift * I - L by power iteration,
+    // as its eigenvalues are the eigenvalues of L in the reverse order. The eigenvalues of L
+    // are at most twice the maximum degree.
+    let adjacency_list = &graph.adjacency_list;
+    let max_degree = adjacency_list.iter().map(Vec::len).max().unwrap_or(0);
+    let shift = 2.0 * max_degree as f64 + 1.0;
+    let multiply = |x: &[f64]| -> Vec<f64> {
+        adjacency_list
+            .iter()
+            .enumerate()
+            .map(|(v, neighbours)| {
+                (shift - neighbours.len() as f64) * x[v]
+                    + neighbours.iter().map(|&u| x[u]).sum::<f64>()
+            })
+            .collect()
+    };
+    // Removes the components of the found eigenvectors from x and scales it to length 1.
+    let orthonormalise = |x: &mut Vec<f64>, eigenvectors: &[Vec<f64>]| {
+        for eigenvector in eigenvectors {
+            let dot: f64 = x.iter().zip(eigenvector).map(|(a, b)| a * b).sum();
+            x.iter_mut()
+                .zip(eigenvector)
+                .for_each(|(a, b)| *a -= dot * b);
+        }
+        let length = x.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if length > 0.0 {
+            x.iter_mut().for_each(|a| *a /= length);
+        }
+    };
+
+    // The constant vector is the eigenvector of the eigenvalue 0.
+    let mut eigenvectors = vec![vec![
+        1.0 / (number_of_vertices as f64).sqrt();
+        number_of_vertices
+    ]];
+    for dimension in 1..=2 {
+        // A fixed start which isn't orthogonal to any eigenvector in practice.
+        let mut x: Vec<f64> = (0..number_of_vertices)
+            .map(|v| ((v + 1) as f64 * dimension as f64 * 1.618_034).sin())
+            .collect();
+        for _ in 0..SPECTRAL_ITERATIONS {
+            orthonormalise(&mut x, &eigenvectors);
+            x = multiply(&x);
+        }
+        orthonormalise(&mut x, &eigenvectors);
+        eigenvectors.push(x);
+    }
+
+    graph.vertices = eigenvectors[1]
+        .iter()
+        .zip(&eigenvectors[2])
+        .map(|(&x, &y)| (x as f32, y as f32))
+        .collect();
+    normalise_vertices(&mut graph.vertices);
+}
+
+/// Moves every vertex to a distinct point of a square grid with a point for every vertex
+/// (and a few spare ones), keeping the vertices roughly where they were. The closest pairs of
+/// a vertex and a point are matched first.
+pub fn snap_to_grid(graph: &mut Graph) {
+    let vertices = &mut graph.vertices;
+    let number_of_vertices = vertices.len();
+    if number_of_vertices == 0 {
+        return;
+    }
+    let side = (number_of_vertices as f64).sqrt().ceil() as usize;
+
+    // We stretch the drawing to fill the grid in both directions.
+    let bounds = |coordinate: fn(&(f32, f32)) -> f32| {
+        let values = vertices.iter().map(coordinate);
+        let min = values.clone().fold(f32::INFINITY, f32::min);
+        let max = values.fold(f32::NEG_INFINITY, f32::max);
+        (min, max)
+    };
+    let ((min_x, max_x), (min_y, max_y)) = (bounds(|v| v.0), bounds(|v| v.1));
+    let to_grid = |value: f32, min: f32, max: f32| {
+        if max > min {
+            (value - min) / (max - min) * (side - 1) as f32
+        } else {
+            (side - 1) as f32 / 2.0
+        }
+    };
+    let stretched: Vec<_> = vertices
+        .iter()
+        .map(|&(x, y)| (to_grid(x, min_x, max_x), to_grid(y, min_y, max_y)))
+        .collect();
+
+    let points: Vec<_> = (0..side * side)
+        .map(|point| ((point % side) as f32, (point / side) as f32))
+        .collect();
+    let mut pairs: Vec<_> = stretched
+        .iter()
+        .enumerate()
+        .flat_map(|(v, &(x, y))| {
+            points
+                .iter()
+                .enumerate()
+                .map(move |(point, &(point_x, point_y))| {
+                    let (dx, dy) = (x - point_x, y - point_y);
+                    (dx * dx + dy * dy, v, point)
+                })
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut placed = vec![false; number_of_vertices];
+    let mut taken = vec![false; points.len()];
+    for (_, v, point) in pairs {
+        if !placed[v] && !taken[point] {
+            placed[v] = true;
+            taken[point] = true;
+            vertices[v] = points[point];
+        }
+    }
+    normalise_vertices(vertices);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{graph_from_edges, GraphGenerator};
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+
+    // Whether the vertices are all at the same distance from the center of the unit square.
+    fn on_a_circle(graph: &Graph) -> bool {
+        let radius = distance(graph.vertices[0], (0.5, 0.5));
+        graph
+            .vertices
+            .iter()
+            .all(|&vertex| (distance(vertex, (0.5, 0.5)) - radius).abs() < 1e-3)
+    }
+
+    #[test]
+    fn layouts_stay_in_the_unit_square() {
+        let mut graphs: Vec<_> = GraphGenerator::families()[..9]
+            .iter()
+            .map(|generator| generator.generate().unwrap())
+            .collect();
+        graphs.push(graph_from_edges("Empty".to_string(), vec![], []));
+        for size in 1..3 {
+            let mut graph = GraphGenerator::Complete { size }.generate().unwrap();
+            // Every vertex on top of the others.
+            graph.vertices.fill((0.3, 0.3));
+            graphs.push(graph);
+        }
+        for graph in graphs {
+            for layout in Layout::ALL {
+                let mut laid_out = graph.clone();
+                layout.apply(&mut laid_out);
+                assert_eq!(laid_out.adjacency_list, graph.adjacency_list);
+                assert_eq!(laid_out.vertices.len(), graph.vertices.len());
+                assert!(laid_out
+                    .vertices
+                    .iter()
+                    .all(|&(x, y)| (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y)));
+            }
+        }
+    }
+
+    #[test]
+    fn force_directed_is_deterministic_and_separates_vertices() {
+        let mut graph = GraphGenerator::Petersen.generate().unwrap();
+        graph.vertices.fill((0.5, 0.5));
+        force_directed(&mut graph);
+        for (v, &vertex) in graph.vertices.iter().enumerate() {
+            for &other in &graph.vertices[v + 1..] {
+                assert!(distance(vertex, other) > 0.01);
+            }
+        }
+        let mut again = GraphGenerator::Petersen.generate().unwrap();
+        again.vertices.fill((0.5, 0.5));
+        force_directed(&mut again);
+        assert_eq!(again.vertices, graph.vertices);
+    }
+
+    #[test]
+    fn circular_and_spectral_draw_cycles_as_polygons() {
+        let mut graph = GraphGenerator::Cycle { length: 8 }.generate().unwrap();
+        graph.vertices.reverse();
+        circular(&mut graph);
+        assert!(on_a_circle(&graph));
+
+        graph.vertices.fill((0.0, 0.0));
+        spectral(&mut graph);
+        assert!(on_a_circle(&graph));
+        // Neighbours are next to each other on the polygon.
+        let side = distance(graph.vertices[0], graph.vertices[1]);
+        for (v, neighbours) in graph.adjacency_list.iter().enumerate() {
+            for &u in neighbours {
+                assert!((distance(graph.vertices[v], graph.vertices[u]) - side).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn snapping_to_grid_gives_distinct_points() {
+        let mut grid = GraphGenerator::Grid {
+            rows: 3,
+            columns: 3,
+        }
+        .generate()
+        .unwrap();
+        let drawing = grid.vertices.clone();
+        snap_to_grid(&mut grid);
+        assert_eq!(grid.vertices, drawing);
+
+        let mut graph = GraphGenerator::Petersen.generate().unwrap();
+        snap_to_grid(&mut graph);
+        for (v, &vertex) in graph.vertices.iter().enumerate() {
+            assert!(!graph.vertices[v + 1..].contains(&vertex));
+        }
+    }
+}
@@ -1,5 +1,17 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod analysis;
 mod app;
-mod game;
+mod files;
+pub mod formats;
+pub mod game;
+pub mod generators;
+pub mod heuristics;
+pub mod human;
+pub mod layouts;
+pub mod mcts;
+pub mod optimal;
+pub mod qlearning;
+pub mod symmetry;
+pub mod tournament;
 pub use app::TemplateApp;
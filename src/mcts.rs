@@ -0,0 +1,651 @@
+use crate::{
+    game::{
+        cop_number_of_moves, decode_cop_move, decode_robber_move, robber_number_of_moves,
+        CopPositions, CopStrategy, Game, GameRng, Graph, RandomCop, RandomRobber, RobberPosition,
+        RobberStrategy, Turn,
+    },
+    symmetry::Symmetries,
+};
+use rand::Rng;
+use std::{any::Any, collections::BTreeMap};
+
+/// The parameters of a Monte Carlo tree search player.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct MctsSettings {
+    /// The number of rollouts for every move.
+    pub rollouts: u32,
+    /// The number of robber moves the search looks ahead, at least 1. The cops win
+    /// a simulated match if they capture the robber within the horizon, else the robber wins.
+    /// The search never looks past the end of the real match.
+    pub horizon: u8,
+}
+
+impl Default for MctsSettings {
+    fn default() -> Self {
+        Self {
+            rollouts: 1000,
+            horizon: 10,
+        }
+    }
+}
+
+// The exploration constant of UCT.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+// Plays the move chosen by the search when it has one, else a random move.
+// The search plays its moves and the rollouts through a Game with these players,
+// so the simulation follows the rules of the real game.
+struct SearchCop {
+    next_move: Option<CopPositions>,
+    random: RandomCop,
+}
+
+impl CopStrategy for SearchCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        match self.next_move.take() {
+            Some(cop_positions) => cop_positions,
+            None => self.random.start(graph, rng),
+        }
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        match self.next_move.take() {
+            Some(cop_positions) => cop_positions,
+            None => self.random.step(graph, cop_positions, robber_position, rng),
+        }
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// The robber counterpart of SearchCop.
+struct SearchRobber {
+    next_move: Option<RobberPosition>,
+    random: RandomRobber,
+}
+
+impl RobberStrategy for SearchRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        match self.next_move.take() {
+            Some(robber_position) => robber_position,
+            None => self.random.start(graph, cop_positions, rng),
+        }
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        match self.next_move.take() {
+            Some(robber_position) => robber_position,
+            None => self.random.step(graph, cop_positions, robber_position, rng),
+        }
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+// A position in the search tree, as the fields of a Game.
+#[derive(Clone)]
+struct State {
+    cop_positions: Option<CopPositions>,
+    robber_position: Option<RobberPosition>,
+    steps_left: u8,
+    turn: Turn,
+}
+
+struct Node {
+    state: State,
+    // Whether the cops won, for positions where the match is over.
+    cop_won: Option<bool>,
+    number_of_moves: u64,
+    // The child node of every expanded move. We use a BTreeMap so ties are broken
+    // the same way every time, and a game can be reproduced from its seed.
+    children: BTreeMap<u64, usize>,
+    visits: u32,
+    cop_wins: u32,
+}
+
+impl Node {
+    fn new(state: State, cop_won: Option<bool>, number_of_moves: u64) -> Self {
+        Self {
+            state,
+            cop_won,
+            number_of_moves,
+            children: BTreeMap::new(),
+            visits: 0,
+            cop_wins: 0,
+        }
+    }
+
+    // The fraction of the rollouts through this node won by the player who moved to it.
+    fn win_rate(&self, turn: Turn) -> f64 {
+        let cop_win_rate = self.cop_wins as f64 / self.visits as f64;
+        if turn == Turn::Cop {
+            cop_win_rate
+        } else {
+            1.0 - cop_win_rate
+        }
+    }
+}
+
+// A Monte Carlo tree search from one position.
+struct Search<'a> {
+    game: Game,
+    number_of_cops: u8,
+    symmetries: &'a Symmetries,
+    nodes: Vec<Node>,
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        graph: &Graph,
+        number_of_cops: u8,
+        symmetries: &'a Symmetries,
+        root: State,
+        rng: &mut GameRng,
+    ) -> Self {
+        let cop = SearchCop {
+            next_move: None,
+            random: RandomCop::new(number_of_cops),
+        };
+        let robber = SearchRobber {
+            next_move: None,
+            random: RandomRobber::new(),
+        };
+        let mut game = Game::new(
+            graph,
+            root.steps_left,
+            Box::new(cop),
+            Box::new(robber),
+            rng.gen(),
+        );
+        // Nobody watches the simulated matches.
+        game.max_recorded_matches = 0;
+        let mut search = Self {
+            game,
+            number_of_cops,
+            symmetries,
+            nodes: vec![],
+        };
+        let root = search.node(root, None);
+        search.nodes.push(root);
+        search
+    }
+
+    fn node(&self, state: State, cop_won: Option<bool>) -> Node {
+        let graph = &self.game.graph;
+        let number_of_moves = match state.turn {
+            Turn::Cop => {
+                cop_number_of_moves(graph, self.number_of_cops, state.cop_positions.as_ref())
+            }
+            Turn::Robber => robber_number_of_moves(graph, state.robber_position),
+            Turn::Over => 0,
+        };
+        Node::new(state, cop_won, number_of_moves)
+    }
+
+    // Sets the game to a state.
+    fn set_state(&mut self, state: &State) {
+        self.game.cop_positions = state.cop_positions.clone();
+        self.game.robber_position = state.robber_position;
+        self.game.steps_left = state.steps_left;
+        self.game.turn = state.turn;
+    }
+
+    // Plays one turn of the game, returning whether the cops won if the match is over.
+    fn update(&mut self) -> Option<bool> {
+        let cop_score = self.game.score[0];
+        self.game.update();
+        (self.game.turn == Turn::Over).then_some(self.game.score[0] > cop_score)
+    }
+
+    // Plays a move from a state and returns the new state.
+    fn play(&mut self, state: &State, choice: u64) -> (State, Option<bool>) {
+        self.set_state(state);
+        let graph = &self.game.graph;
+        match state.turn {
+            Turn::Cop => {
+                let position = state
+                    .cop_positions
+                    .as_ref()
+                    .map(|cop_positions| (cop_positions, state.robber_position.unwrap()));
+                let cop_positions = decode_cop_move(
+                    graph,
+                    self.number_of_cops,
+                    self.symmetries,
+                    0,
+                    position,
+                    choice,
+                );
+                let cop = self.game.cop.as_any_mut().downcast_mut::<SearchCop>();
+                cop.unwrap().next_move = Some(cop_positions);
+            }
+            Turn::Robber => {
+                let robber_position =
+                    decode_robber_move(graph, self.symmetries, 0, state.robber_position, choice);
+                let robber = self.game.robber.as_any_mut().downcast_mut::<SearchRobber>();
+                robber.unwrap().next_move = Some(robber_position);
+            }
+            Turn::Over => unreachable!("no moves after the match is over"),
+        }
+        let cop_won = self.update();
+        let state = State {
+            cop_positions: self.game.cop_positions.clone(),
+            robber_position: self.game.robber_position,
+            steps_left: self.game.steps_left,
+            turn: self.game.turn,
+        };
+        (state, cop_won)
+    }
+
+    // Plays random moves from a state until the match is over, returning whether the cops won.
+    fn rollout(&mut self, state: &State) -> bool {
+        self.set_state(state);
+        loop {
+            if let Some(cop_won) = self.update() {
+                return cop_won;
+            }
+        }
+    }
+
+    // Chooses a move of a node which doesn't have a child yet.
+    fn untried_move(&self, node: usize, rng: &mut GameRng) -> u64 {
+        let node = &self.nodes[node];
+        let number_of_untried_moves = node.number_of_moves - node.children.len() as u64;
+        if number_of_untried_moves * 2 > node.number_of_moves {
+            // Most moves are untried, e.g. in the start position, so we guess.
+            loop {
+                let choice = rng.gen_range(0..node.number_of_moves);
+                if !node.children.contains_key(&choice) {
+                    return choice;
+                }
+            }
+        }
+        let index = rng.gen_range(0..number_of_untried_moves);
+        (0..node.number_of_moves)
+            .filter(|choice| !node.children.contains_key(choice))
+            .nth(index as usize)
+            .unwrap()
+    }
+
+    // The child with the highest upper confidence bound.
+    fn select(&self, node: usize) -> usize {
+        let parent = &self.nodes[node];
+        let log_visits = (parent.visits as f64).ln();
+        let score = |child: usize| {
+            let child = &self.nodes[child];
+            child.win_rate(parent.state.turn)
+                + EXPLORATION * (log_visits / child.visits as f64).sqrt()
+        };
+        parent
+            .children
+            .values()
+            .copied()
+            .max_by(|&child, &other_child| score(child).total_cmp(&score(other_child)))
+            .unwrap()
+    }
+
+    fn iterate(&mut self, rng: &mut GameRng) {
+        // Selection: we go down the tree while every move has been tried.
+        let mut path = vec![0];
+        let mut node = 0;
+        while self.nodes[node].cop_won.is_none()
+            && self.nodes[node].children.len() as u64 == self.nodes[node].number_of_moves
+        {
+            node = self.select(node);
+            path.push(node);
+        }
+
+        // Expansion and simulation.
+        let cop_won = match self.nodes[node].cop_won {
+            Some(cop_won) => cop_won,
+            None => {
+                let choice = self.untried_move(node, rng);
+                let state = self.nodes[node].state.clone();
+                let (child_state, cop_won) = self.play(&state, choice);
+                let child = self.node(child_state, cop_won);
+                let child_index = self.nodes.len();
+                self.nodes.push(child);
+                self.nodes[node].children.insert(choice, child_index);
+                path.push(child_index);
+                match cop_won {
+                    Some(cop_won) => cop_won,
+                    None => {
+                        let child_state = self.nodes[child_index].state.clone();
+                        self.rollout(&child_state)
+                    }
+                }
+            }
+        };
+
+        // Backpropagation.
+        for node in path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            node.cop_wins += cop_won as u32;
+        }
+    }
+
+    // Searches with the given number of rollouts and returns the most visited move.
+    fn best_move(mut self, rollouts: u32, rng: &mut GameRng) -> u64 {
+        for _ in 0..rollouts.max(1) {
+            self.iterate(rng);
+        }
+        let root = &self.nodes[0];
+        root.children
+            .iter()
+            .max_by_key(|&(&choice, &child)| (self.nodes[child].visits, std::cmp::Reverse(choice)))
+            .map(|(&choice, _)| choice)
+            .unwrap()
+    }
+}
+
+/// A cop which chooses every move by a Monte Carlo tree search with random rollouts.
+/// It doesn't learn between moves, so it needs no training and no solved game.
+pub struct MctsCop {
+    number_of_cops: u8,
+    settings: MctsSettings,
+    // The number of robber moves left in the real match.
+    steps_left: u8,
+    // The symmetries the search encodes moves with (only the identity), computed for the
+    // first search of the player.
+    symmetries: Option<Symmetries>,
+}
+
+impl MctsCop {
+    pub fn new(number_of_cops: u8, settings: MctsSettings) -> Self {
+        Self {
+            number_of_cops,
+            settings,
+            steps_left: u8::MAX,
+            symmetries: None,
+        }
+    }
+
+    pub fn settings(&self) -> &MctsSettings {
+        &self.settings
+    }
+
+    // The simulated matches end at the horizon or at the end of the real match.
+    fn search_steps_left(&self) -> u8 {
+        self.steps_left.min(self.settings.horizon.max(1))
+    }
+
+    fn search(&mut self, graph: &Graph, state: State, rng: &mut GameRng) -> CopPositions {
+        let symmetries = self
+            .symmetries
+            .get_or_insert_with(|| Symmetries::trivial(graph));
+        let search = Search::new(graph, self.number_of_cops, symmetries, state.clone(), rng);
+        let choice = search.best_move(self.settings.rollouts, rng);
+        let position = state
+            .cop_positions
+            .as_ref()
+            .map(|cop_positions| (cop_positions, state.robber_position.unwrap()));
+        decode_cop_move(graph, self.number_of_cops, symmetries, 0, position, choice)
+    }
+}
+
+impl CopStrategy for MctsCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        let state = State {
+            cop_positions: None,
+            robber_position: None,
+            steps_left: self.search_steps_left(),
+            turn: Turn::Cop,
+        };
+        self.search(graph, state, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        let state = State {
+            cop_positions: Some(cop_positions.clone()),
+            robber_position: Some(robber_position),
+            steps_left: self.search_steps_left(),
+            turn: Turn::Cop,
+        };
+        self.search(graph, state, rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn set_steps_left(&mut self, steps_left: u8) {
+        self.steps_left = steps_left;
+    }
+
+    fn is_slow(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A robber which chooses every move by a Monte Carlo tree search, see [`MctsCop`].
+pub struct MctsRobber {
+    number_of_cops: u8,
+    settings: MctsSettings,
+    // The number of robber moves left in the real match.
+    steps_left: u8,
+    // The symmetries the search encodes moves with (only the identity), computed for the
+    // first search of the player.
+    symmetries: Option<Symmetries>,
+}
+
+impl MctsRobber {
+    pub fn new(number_of_cops: u8, settings: MctsSettings) -> Self {
+        Self {
+            number_of_cops,
+            settings,
+            steps_left: u8::MAX,
+            symmetries: None,
+        }
+    }
+
+    pub fn settings(&self) -> &MctsSettings {
+        &self.settings
+    }
+
+    // The simulated matches end at the horizon or at the end of the real match.
+    fn search_steps_left(&self) -> u8 {
+        self.steps_left.min(self.settings.horizon.max(1))
+    }
+
+    fn search(&mut self, graph: &Graph, state: State, rng: &mut GameRng) -> RobberPosition {
+        let robber_position = state.robber_position;
+        let symmetries = self
+            .symmetries
+            .get_or_insert_with(|| Symmetries::trivial(graph));
+        let search = Search::new(graph, self.number_of_cops, symmetries, state, rng);
+        let choice = search.best_move(self.settings.rollouts, rng);
+        decode_robber_move(graph, symmetries, 0, robber_position, choice)
+    }
+}
+
+impl RobberStrategy for MctsRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let state = State {
+            cop_positions: Some(cop_positions.clone()),
+            robber_position: None,
+            steps_left: self.search_steps_left(),
+            turn: Turn::Robber,
+        };
+        self.search(graph, state, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let state = State {
+            cop_positions: Some(cop_positions.clone()),
+            robber_position: Some(robber_position),
+            steps_left: self.search_steps_left(),
+            turn: Turn::Robber,
+        };
+        self.search(graph, state, rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    fn set_steps_left(&mut self, steps_left: u8) {
+        self.steps_left = steps_left;
+    }
+
+    fn is_slow(&self) -> bool {
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::template_graphs;
+    use rand::SeedableRng;
+
+    const SETTINGS: MctsSettings = MctsSettings {
+        rollouts: 200,
+        horizon: 5,
+    };
+
+    #[test]
+    fn cop_captures_a_robber_next_to_it() {
+        let graph = &template_graphs()[1];
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut cop = MctsCop::new(1, SETTINGS);
+        assert_eq!(cop.step(graph, &vec![1], 2, &mut rng), vec![2]);
+        assert_eq!(cop.step(graph, &vec![4], 3, &mut rng), vec![3]);
+    }
+
+    #[test]
+    fn robber_keeps_away_from_the_cop() {
+        let graph = &template_graphs()[2];
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut robber = MctsRobber::new(1, SETTINGS);
+        for _ in 0..10 {
+            // Moving to 1 lets the cop at 0 capture the robber.
+            assert_ne!(robber.step(graph, &vec![0], 2, &mut rng), 1);
+        }
+    }
+
+    #[test]
+    fn search_stops_at_the_end_of_the_match() {
+        let mut cop = MctsCop::new(1, SETTINGS);
+        assert_eq!(cop.search_steps_left(), 5);
+        cop.set_steps_left(2);
+        assert_eq!(cop.search_steps_left(), 2);
+
+        let mut robber = MctsRobber::new(
+            1,
+            MctsSettings {
+                horizon: 0,
+                ..SETTINGS
+            },
+        );
+        assert_eq!(robber.search_steps_left(), 1);
+        robber.set_steps_left(0);
+        assert_eq!(robber.search_steps_left(), 0);
+    }
+
+    #[test]
+    fn cop_beats_the_random_cop() {
+        let cop_wins = |cop: Box<dyn CopStrategy>| {
+            let mut game = Game::new(
+                &template_graphs()[1],
+                2,
+                cop,
+                Box::new(RandomRobber::new()),
+                0,
+            );
+            while game.score[0] + game.score[1] < 50 {
+                game.update();
+            }
+            game.score[0]
+        };
+        let mcts = cop_wins(Box::new(MctsCop::new(1, SETTINGS)));
+        let random = cop_wins(Box::new(RandomCop::new(1)));
+        assert!(mcts > random, "{mcts} <= {random}");
+    }
+}
@@ -0,0 +1,495 @@
+use crate::game::{
+    possible_cop_moves, CopPositions, CopStrategy, GameRng, Graph, RobberPosition, RobberStrategy,
+    Turn,
+};
+use rand::seq::SliceRandom;
+use std::any::Any;
+
+// The value of a position the cops can never win from.
+const NEVER: u32 = u32::MAX;
+
+/// The largest number of positions we solve the game for.
+pub const MAX_POSITIONS: usize = 2_000_000;
+
+/// The game solved by retrograde analysis for a graph and a number of cops.
+///
+/// For every position we store the smallest number of steps left for which the cops
+/// can force a capture. A position with `steps_left` steps left is a cop win
+/// if and only if its value is at most `steps_left`, so a single table covers every step limit.
+pub struct Solution {
+    number_of_vertices: usize,
+    number_of_cops: u8,
+    // Both tables are indexed by position_index(cop_positions, robber_position).
+    cop_turn: Vec<u32>,
+    robber_turn: Vec<u32>,
+}
+
+/// Whether the game has at most [`MAX_POSITIONS`] positions, so we can solve it.
+pub fn is_solvable(graph: &Graph, number_of_cops: u8) -> bool {
+    graph
+        .vertices
+        .len()
+        .checked_pow(number_of_cops as u32 + 1)
+        .map_or(false, |positions| positions <= MAX_POSITIONS)
+}
+
+/// Checks that the game is small enough for the optimal strategies to solve it.
+pub fn check_solvable(graph: &Graph, number_of_cops: u8) -> Result<(), String> {
+    if is_solvable(graph, number_of_cops) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The game on {} with {number_of_cops} cops has more than {MAX_POSITIONS} positions, \
+             too many to solve.",
+            graph.name
+        ))
+    }
+}
+
+impl Solution {
+    /// Solves the game. The number of positions is `vertices^(number_of_cops + 1)`,
+    /// so this is only feasible for small graphs and few cops, see [`check_solvable`].
+    pub fn new(graph: &Graph, number_of_cops: u8) -> Solution {
+        let number_of_vertices = graph.vertices.len();
+        let number_of_positions = number_of_vertices
+            .checked_pow(number_of_cops as u32 + 1)
+            .expect("too many positions to solve the game");
+
+        let mut solution = Solution {
+            number_of_vertices,
+            number_of_cops,
+            cop_turn: vec![NEVER; number_of_positions],
+            robber_turn: vec![NEVER; number_of_positions],
+        };
+
+        // For each robber turn position, the number of robber moves
+        // which aren't yet known to lose.
+        let mut robber_moves_left = vec![0; number_of_positions];
+
+        // We label positions in increasing order of their value.
+        // current holds the positions with the current value which still need to be
+        // propagated to their predecessors, next holds the positions with the next value.
+        let mut current = Vec::new();
+        for (index, moves_left) in robber_moves_left.iter_mut().enumerate() {
+            let (cop_positions, robber_position) = solution.position(index);
+
+            // On their turn, the cops win if a cop is at or next to the robber.
+            if cop_positions.iter().any(|&cop_position| {
+                cop_position == robber_position
+                    || graph.adjacency_list[cop_position].contains(&robber_position)
+            }) {
+                solution.cop_turn[index] = 1;
+                current.push((Turn::Cop, index));
+            }
+
+            // On their turn, the robber loses if every move walks into a cop.
+            let safe_moves = graph.adjacency_list[robber_position]
+                .iter()
+                .chain(std::iter::once(&robber_position))
+                .filter(|robber_position| !cop_positions.contains(robber_position))
+                .count();
+            *moves_left = safe_moves;
+            if safe_moves == 0 {
+                solution.robber_turn[index] = 1;
+                current.push((Turn::Robber, index));
+            }
+        }
+
+        let mut value = 1;
+        while !current.is_empty() {
+            let mut next = Vec::new();
+            // Cop turn predecessors get the same value, so we keep pushing to current while iterating.
+            let mut i = 0;
+            while i < current.len() {
+                let (turn, index) = current[i];
+                i += 1;
+
+                let (cop_positions, robber_position) = solution.position(index);
+                match turn {
+                    Turn::Cop => {
+                        // A robber move into a cop was never counted as a safe move.
+                        if cop_positions.contains(&robber_position) {
+                            continue;
+                        }
+                        // The robber could have moved here from itself or any of its neighbours.
+                        for &previous_robber_position in graph.adjacency_list[robber_position]
+                            .iter()
+                            .chain(std::iter::once(&robber_position))
+                        {
+                            let previous =
+                                solution.position_index(&cop_positions, previous_robber_position);
+                            if solution.robber_turn[previous] != NEVER {
+                                continue;
+                            }
+                            robber_moves_left[previous] -= 1;
+                            if robber_moves_left[previous] == 0 {
+                                // This was the robber's best move, so it decides the value.
+                                solution.robber_turn[previous] = value + 1;
+                                next.push((Turn::Robber, previous));
+                            }
+                        }
+                    }
+                    Turn::Robber => {
+                        // Moves are symmetric, so the cops could have moved here
+                        // from any position they can move to from here.
+                        for previous_cop_positions in possible_cop_moves(graph, &cop_positions) {
+                            let previous =
+                                solution.position_index(&previous_cop_positions, robber_position);
+                            if solution.cop_turn[previous] == NEVER {
+                                solution.cop_turn[previous] = value;
+                                current.push((Turn::Cop, previous));
+                            }
+                        }
+                    }
+                    Turn::Over => {}
+                }
+            }
+            current = next;
+            value += 1;
+        }
+
+        solution
+    }
+
+    fn position_index(
+        &self,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) -> usize {
+        let mut index = 0;
+        for &cop_position in cop_positions.iter().rev() {
+            index = index * self.number_of_vertices + cop_position;
+        }
+        index * self.number_of_vertices + robber_position
+    }
+
+    fn position(&self, mut index: usize) -> (CopPositions, RobberPosition) {
+        let robber_position = index % self.number_of_vertices;
+        index /= self.number_of_vertices;
+        let mut cop_positions = vec![];
+        for _ in 0..self.number_of_cops {
+            cop_positions.push(index % self.number_of_vertices);
+            index /= self.number_of_vertices;
+        }
+        (cop_positions, robber_position)
+    }
+
+    /// The smallest number of steps left for which the cops can force a capture
+    /// when it's `turn`'s turn, or None if the robber can escape forever.
+    pub fn value(
+        &self,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        turn: Turn,
+    ) -> Option<u32> {
+        let value = match turn {
+            Turn::Cop => self.cop_turn[self.position_index(cop_positions, robber_position)],
+            Turn::Robber => self.robber_turn[self.position_index(cop_positions, robber_position)],
+            Turn::Over if cop_positions.contains(&robber_position) => 0,
+            Turn::Over => NEVER,
+        };
+        (value != NEVER).then_some(value)
+    }
+
+    /// Whether the cops win the position with perfect play.
+    pub fn cop_wins(
+        &self,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        turn: Turn,
+        steps_left: u8,
+    ) -> bool {
+        self.value(cop_positions, robber_position, turn)
+            .map_or(false, |value| value <= steps_left as u32)
+    }
+
+    /// The number of steps the cops need to capture the robber from the start
+    /// with perfect play by both sides, or None if the robber can escape forever.
+    pub fn capture_time(&self) -> Option<u32> {
+        let value = (0..self.number_of_vertices.pow(self.number_of_cops as u32))
+            .map(|index| self.start_value(&self.position(index * self.number_of_vertices).0))
+            .min()
+            .unwrap_or(NEVER);
+        (value != NEVER).then_some(value)
+    }
+
+    // The value of the cops starting at cop_positions, assuming the robber
+    // chooses the best starting position.
+    fn start_value(&self, cop_positions: &CopPositions) -> u32 {
+        (0..self.number_of_vertices)
+            .map(|robber_position| self.robber_start_value(cop_positions, robber_position))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn robber_start_value(
+        &self,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) -> u32 {
+        if cop_positions.contains(&robber_position) {
+            0
+        } else {
+            self.cop_turn[self.position_index(cop_positions, robber_position)]
+        }
+    }
+}
+
+// Chooses uniformly among the options with the best value.
+pub(crate) fn choose_best<T: Clone>(
+    options: Vec<(T, u32)>,
+    minimize: bool,
+    rng: &mut GameRng,
+) -> T {
+    let best = if minimize {
+        options.iter().map(|(_, value)| *value).min()
+    } else {
+        options.iter().map(|(_, value)| *value).max()
+    };
+    let best_options: Vec<_> = options
+        .into_iter()
+        .filter(|(_, value)| Some(*value) == best)
+        .collect();
+    // There is always at least one option, as a player can always stay in place,
+    // so callers must not pass an empty list.
+    best_options.choose(rng).unwrap().0.clone()
+}
+
+/// A cop which plays perfectly. It captures the robber as fast as possible
+/// whenever it can.
+pub struct OptimalCop {
+    number_of_cops: u8,
+    solution: Option<Solution>,
+}
+
+impl OptimalCop {
+    pub fn new(number_of_cops: u8) -> Self {
+        Self {
+            number_of_cops,
+            solution: None,
+        }
+    }
+
+    /// The solution, if the game has already been solved.
+    pub fn solution(&self) -> Option<&Solution> {
+        self.solution.as_ref()
+    }
+}
+
+impl CopStrategy for OptimalCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        let number_of_cops = self.number_of_cops;
+        let solution = self
+            .solution
+            .get_or_insert_with(|| Solution::new(graph, number_of_cops));
+
+        let options = (0..graph.vertices.len().pow(number_of_cops as u32))
+            .map(|index| {
+                let (cop_positions, _) = solution.position(index * graph.vertices.len());
+                let value = solution.start_value(&cop_positions);
+                (cop_positions, value)
+            })
+            .collect();
+        choose_best(options, true, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        let number_of_cops = self.number_of_cops;
+        let solution = self
+            .solution
+            .get_or_insert_with(|| Solution::new(graph, number_of_cops));
+
+        let options = possible_cop_moves(graph, cop_positions)
+            .into_iter()
+            .map(|new_cop_positions| {
+                let value = if new_cop_positions.contains(&robber_position) {
+                    0
+                } else {
+                    solution.robber_turn
+                        [solution.position_index(&new_cop_positions, robber_position)]
+                };
+                (new_cop_positions, value)
+            })
+            .collect();
+        choose_best(options, true, rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    // The game is solved for the first move.
+    fn is_slow(&self) -> bool {
+        self.solution.is_none()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A robber which plays perfectly. It escapes whenever it can,
+/// and otherwise delays the capture as long as possible.
+pub struct OptimalRobber {
+    number_of_cops: u8,
+    solution: Option<Solution>,
+}
+
+impl OptimalRobber {
+    pub fn new(number_of_cops: u8) -> Self {
+        Self {
+            number_of_cops,
+            solution: None,
+        }
+    }
+
+    /// The solution, if the game has already been solved.
+    pub fn solution(&self) -> Option<&Solution> {
+        self.solution.as_ref()
+    }
+}
+
+impl RobberStrategy for OptimalRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let number_of_cops = self.number_of_cops;
+        let solution = self
+            .solution
+            .get_or_insert_with(|| Solution::new(graph, number_of_cops));
+
+        let options = (0..graph.vertices.len())
+            .map(|robber_position| {
+                let value = solution.robber_start_value(cop_positions, robber_position);
+                (robber_position, value)
+            })
+            .collect();
+        choose_best(options, false, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let number_of_cops = self.number_of_cops;
+        let solution = self
+            .solution
+            .get_or_insert_with(|| Solution::new(graph, number_of_cops));
+
+        let options = graph.adjacency_list[robber_position]
+            .iter()
+            .chain(std::iter::once(&robber_position))
+            .map(|&new_robber_position| {
+                let value = solution.robber_start_value(cop_positions, new_robber_position);
+                (new_robber_position, value)
+            })
+            .collect();
+        choose_best(options, false, rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        _cop_positions: &CopPositions,
+        _robber_position: RobberPosition,
+    ) {
+    }
+
+    // The game is solved for the first move.
+    fn is_slow(&self) -> bool {
+        self.solution.is_none()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{template_graphs, StrategyRegistry};
+    use rand::SeedableRng;
+
+    #[test]
+    fn capture_times_of_the_templates() {
+        let graphs = template_graphs();
+        let capture_time = |graph: usize, number_of_cops| {
+            Solution::new(&graphs[graph], number_of_cops).capture_time()
+        };
+        // Path2, Path5 and Hexagon.
+        assert_eq!(capture_time(0, 1), Some(1));
+        assert_eq!(capture_time(1, 1), Some(2));
+        assert_eq!(capture_time(2, 1), None);
+        assert_eq!(capture_time(2, 2), Some(1));
+    }
+
+    #[test]
+    fn values_on_a_path() {
+        let solution = Solution::new(&template_graphs()[1], 1);
+        // The cop is next to the robber.
+        assert_eq!(solution.value(&vec![1], 2, Turn::Cop), Some(1));
+        // The cop walks from one end to the other while the robber waits at its end.
+        assert_eq!(solution.value(&vec![0], 4, Turn::Cop), Some(4));
+        assert_eq!(solution.value(&vec![0], 4, Turn::Robber), Some(5));
+        assert_eq!(solution.value(&vec![2], 0, Turn::Cop), Some(2));
+        assert!(solution.cop_wins(&vec![0], 4, Turn::Cop, 4));
+        assert!(!solution.cop_wins(&vec![0], 4, Turn::Cop, 3));
+    }
+
+    #[test]
+    fn robber_escapes_one_cop_on_the_hexagon() {
+        let solution = Solution::new(&template_graphs()[2], 1);
+        assert_eq!(solution.value(&vec![0], 3, Turn::Cop), None);
+        assert_eq!(solution.value(&vec![0], 1, Turn::Cop), Some(1));
+    }
+
+    #[test]
+    fn players_are_slow_until_they_have_solved_the_game() {
+        let graph = &template_graphs()[2];
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut cop = OptimalCop::new(1);
+        let mut robber = OptimalRobber::new(1);
+        assert!(cop.is_slow() && robber.is_slow());
+        let cop_positions = cop.start(graph, &mut rng);
+        robber.start(graph, &cop_positions, &mut rng);
+        assert!(!cop.is_slow() && !robber.is_slow());
+    }
+
+    #[test]
+    fn large_games_are_not_solvable() {
+        let graph = &template_graphs()[2];
+        assert!(check_solvable(graph, 2).is_ok());
+        assert!(check_solvable(graph, 8).is_err());
+
+        let registry = StrategyRegistry::default();
+        assert!(registry.check_cop("Optimal", graph, 2).is_ok());
+        assert!(registry.check_robber("Optimal", graph, 8).is_err());
+        assert!(registry.check_cop("Random", graph, 8).is_ok());
+        assert!(registry.check_cop("Unknown", graph, 1).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+use crate::{
+    game::{
+        cop_number_of_moves, decode_cop_move, decode_robber_move, learning_symmetries,
+        robber_number_of_moves, CopPositions, CopStrategy, GameRng, Graph, RobberPosition,
+        RobberStrategy, StateKey,
+    },
+    symmetry::Symmetries,
+};
+use rand::Rng;
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+};
+
+/// The parameters of a Q-learning player.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct QLearningSettings {
+    /// How far a value moves towards its new estimate in each update, between 0 and 1.
+    pub learning_rate: f64,
+    /// How much the value of the next position counts, between 0 and 1.
+    /// Lower values prefer winning (or delaying a loss) sooner.
+    pub discount: f64,
+    /// The probability of playing a random move instead of the best one.
+    pub epsilon: f64,
+    /// Whether positions which are equivalent under a symmetry of the graph share a Q-table.
+    pub use_symmetries: bool,
+}
+
+impl Default for QLearningSettings {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            discount: 0.9,
+            epsilon: 0.1,
+            use_symmetries: true,
+        }
+    }
+}
+
+// The rewards of the last move of a match. Every other move has no reward.
+const WIN_REWARD: f64 = 1.0;
+const LOSS_REWARD: f64 = -1.0;
+
+/// The learned values of the moves of a position. Like a MENACE bag, we only store
+/// the values of the moves which have been updated, the other moves have the value 0.
+#[derive(Clone)]
+pub struct QTable {
+    number_of_moves: u64,
+    values: BTreeMap<u64, f64>,
+}
+
+impl QTable {
+    fn new(number_of_moves: u64) -> Self {
+        Self {
+            number_of_moves,
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn number_of_moves(&self) -> u64 {
+        self.number_of_moves
+    }
+
+    pub fn value(&self, choice: u64) -> f64 {
+        self.values.get(&choice).copied().unwrap_or(0.0)
+    }
+
+    /// The moves and their values in the order of the moves.
+    pub fn values(&self) -> impl Iterator<Item = (u64, f64)> + '_ {
+        (0..self.number_of_moves).map(|choice| (choice, self.value(choice)))
+    }
+
+    /// The limit moves with the highest values, in descending order of the values.
+    pub fn best_values(&self, limit: usize) -> Vec<(u64, f64)> {
+        // Every move with the value 0 is the same, so we only need the first limit of them.
+        let mut values: Vec<_> = self
+            .values()
+            .filter(|&(choice, _)| !self.values.contains_key(&choice))
+            .take(limit)
+            .chain(self.values.iter().map(|(&choice, &value)| (choice, value)))
+            .collect();
+        values.sort_by(|(choice, value), (other_choice, other_value)| {
+            other_value.total_cmp(value).then(choice.cmp(other_choice))
+        });
+        values.truncate(limit);
+        values
+    }
+
+    fn number_of_unchanged_moves(&self) -> u64 {
+        self.number_of_moves - self.values.len() as u64
+    }
+
+    fn max_value(&self) -> f64 {
+        let unchanged = (self.number_of_unchanged_moves() > 0).then_some(0.0);
+        self.values
+            .values()
+            .copied()
+            .chain(unchanged)
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    // Chooses uniformly between the moves with the highest value.
+    fn best_move(&self, rng: &mut GameRng) -> u64 {
+        let max_value = self.max_value();
+        let best: Vec<_> = self
+            .values
+            .iter()
+            .filter(|&(_, &value)| value == max_value)
+            .map(|(&choice, _)| choice)
+            .collect();
+        let unchanged = if max_value == 0.0 {
+            self.number_of_unchanged_moves()
+        } else {
+            0
+        };
+
+        let index = rng.gen_range(0..best.len() as u64 + unchanged);
+        if index < best.len() as u64 {
+            return best[index as usize];
+        }
+
+        // We find the index-th unchanged move like Bag::choose.
+        let mut choice = index - best.len() as u64;
+        for &changed_choice in self.values.keys() {
+            if changed_choice <= choice {
+                choice += 1;
+            } else {
+                break;
+            }
+        }
+        choice
+    }
+
+    // Moves the value of a move towards the target.
+    fn update(&mut self, choice: u64, target: f64, learning_rate: f64) {
+        let value = self.value(choice);
+        self.values
+            .insert(choice, value + learning_rate * (target - value));
+    }
+}
+
+// The Q-tables and the last move of a player, shared by the cop and the robber.
+struct QLearning {
+    settings: QLearningSettings,
+    // Computed when the first match starts, see MenaceCop.
+    symmetries: Option<Symmetries>,
+    tables: HashMap<StateKey, QTable>,
+    // The last move is updated once we know the next position or the result of the match.
+    last_move: Option<(StateKey, u64)>,
+}
+
+impl QLearning {
+    fn new(settings: QLearningSettings) -> Self {
+        Self {
+            settings,
+            symmetries: None,
+            tables: HashMap::new(),
+            last_move: None,
+        }
+    }
+
+    fn symmetries(&self, graph: &Graph) -> Cow<'_, Symmetries> {
+        match &self.symmetries {
+            Some(symmetries) => Cow::Borrowed(symmetries),
+            None => Cow::Owned(Symmetries::trivial(graph)),
+        }
+    }
+
+    fn update_last_move(&mut self, target: f64) {
+        if let Some((key, choice)) = self.last_move.take() {
+            // We added the table when we played the move, so we can unwrap.
+            let table = self.tables.get_mut(&key).unwrap();
+            table.update(choice, target, self.settings.learning_rate);
+        }
+    }
+
+    // Updates the last move with the value of the new position, and chooses a move epsilon-greedily.
+    fn choose(&mut self, key: StateKey, number_of_moves: u64, rng: &mut GameRng) -> u64 {
+        let next_value = self.tables.get(&key).map_or(0.0, QTable::max_value);
+        self.update_last_move(self.settings.discount * next_value);
+
+        let table = self
+            .tables
+            .entry(key)
+            .or_insert_with(|| QTable::new(number_of_moves));
+        let choice = if rng.gen_bool(self.settings.epsilon.clamp(0.0, 1.0)) {
+            rng.gen_range(0..number_of_moves)
+        } else {
+            table.best_move(rng)
+        };
+        self.last_move = Some((key, choice));
+        choice
+    }
+
+    fn end(&mut self, won: bool) {
+        self.update_last_move(if won { WIN_REWARD } else { LOSS_REWARD });
+    }
+}
+
+/// A cop which learns the value of every move by tabular Q-learning. It uses the same
+/// positions and moves as [`crate::game::MenaceCop`], with a Q-table instead of a bag.
+pub struct QLearningCop {
+    number_of_cops: u8,
+    learning: QLearning,
+}
+
+impl QLearningCop {
+    pub fn new(number_of_cops: u8, settings: QLearningSettings) -> Self {
+        Self {
+            number_of_cops,
+            learning: QLearning::new(settings),
+        }
+    }
+
+    pub fn settings(&self) -> &QLearningSettings {
+        &self.learning.settings
+    }
+
+    /// The Q-tables keyed by the encoded position, like `MenaceCop::bags`.
+    pub fn tables(&self) -> &HashMap<StateKey, QTable> {
+        &self.learning.tables
+    }
+
+    /// The symmetries of the graph used to share Q-tables, or only the identity before the first match.
+    pub fn symmetries(&self, graph: &Graph) -> Cow<'_, Symmetries> {
+        self.learning.symmetries(graph)
+    }
+
+    /// The Q-table for a position, or a new Q-table if the position hasn't been played yet.
+    /// The position is None for the start position.
+    pub fn table(
+        &self,
+        graph: &Graph,
+        position: Option<(&CopPositions, RobberPosition)>,
+    ) -> QTable {
+        let (key, _) = self.table_key(graph, &self.symmetries(graph), position);
+        self.learning.tables.get(&key).cloned().unwrap_or_else(|| {
+            QTable::new(cop_number_of_moves(
+                graph,
+                self.number_of_cops,
+                position.map(|(cop_positions, _)| cop_positions),
+            ))
+        })
+    }
+
+    /// The cop positions after a move from the Q-table of a position.
+    /// The position is None for the start position.
+    pub fn decode_move(
+        &self,
+        graph: &Graph,
+        position: Option<(&CopPositions, RobberPosition)>,
+        choice: u64,
+    ) -> CopPositions {
+        let symmetries = self.symmetries(graph);
+        let (_, automorphism) = self.table_key(graph, &symmetries, position);
+        decode_cop_move(
+            graph,
+            self.number_of_cops,
+            &symmetries,
+            automorphism,
+            position,
+            choice,
+        )
+    }
+
+    // The key of the Q-table for a position and the automorphism which maps the position to it.
+    fn table_key(
+        &self,
+        graph: &Graph,
+        symmetries: &Symmetries,
+        position: Option<(&CopPositions, RobberPosition)>,
+    ) -> (StateKey, usize) {
+        symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            position.map(|(cop_positions, _)| cop_positions),
+            position.map(|(_, robber_position)| robber_position),
+        )
+    }
+
+    fn choose(
+        &mut self,
+        graph: &Graph,
+        position: Option<(&CopPositions, RobberPosition)>,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        let use_symmetries = self.learning.settings.use_symmetries;
+        let symmetries = self
+            .learning
+            .symmetries
+            .take()
+            .unwrap_or_else(|| learning_symmetries(graph, use_symmetries));
+        let (key, automorphism) = self.table_key(graph, &symmetries, position);
+        let number_of_moves = cop_number_of_moves(
+            graph,
+            self.number_of_cops,
+            position.map(|(cop_positions, _)| cop_positions),
+        );
+
+        let choice = self.learning.choose(key, number_of_moves, rng);
+        let new_cop_positions = decode_cop_move(
+            graph,
+            self.number_of_cops,
+            &symmetries,
+            automorphism,
+            position,
+            choice,
+        );
+        self.learning.symmetries = Some(symmetries);
+        new_cop_positions
+    }
+}
+
+impl CopStrategy for QLearningCop {
+    fn start(&mut self, graph: &Graph, rng: &mut GameRng) -> CopPositions {
+        self.choose(graph, None, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> CopPositions {
+        self.choose(graph, Some((cop_positions, robber_position)), rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) {
+        self.learning.end(cop_positions.contains(&robber_position));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A robber which learns by tabular Q-learning, see [`QLearningCop`].
+pub struct QLearningRobber {
+    number_of_cops: u8,
+    learning: QLearning,
+}
+
+impl QLearningRobber {
+    pub fn new(number_of_cops: u8, settings: QLearningSettings) -> Self {
+        Self {
+            number_of_cops,
+            learning: QLearning::new(settings),
+        }
+    }
+
+    pub fn settings(&self) -> &QLearningSettings {
+        &self.learning.settings
+    }
+
+    /// The Q-tables keyed by the encoded position, like `MenaceRobber::bags`.
+    pub fn tables(&self) -> &HashMap<StateKey, QTable> {
+        &self.learning.tables
+    }
+
+    /// The symmetries of the graph used to share Q-tables, or only the identity before the first match.
+    pub fn symmetries(&self, graph: &Graph) -> Cow<'_, Symmetries> {
+        self.learning.symmetries(graph)
+    }
+
+    /// The Q-table for a position, or a new Q-table if the position hasn't been played yet.
+    /// The robber position is None for start positions.
+    pub fn table(
+        &self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+    ) -> QTable {
+        let (key, _) = self.symmetries(graph).canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        self.learning
+            .tables
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| QTable::new(robber_number_of_moves(graph, robber_position)))
+    }
+
+    /// The robber position after a move from the Q-table of a position.
+    /// The robber position is None for start positions.
+    pub fn decode_move(
+        &self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+        choice: u64,
+    ) -> RobberPosition {
+        let symmetries = self.symmetries(graph);
+        let (_, automorphism) = symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        decode_robber_move(graph, &symmetries, automorphism, robber_position, choice)
+    }
+
+    fn choose(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: Option<RobberPosition>,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        let use_symmetries = self.learning.settings.use_symmetries;
+        let symmetries = self
+            .learning
+            .symmetries
+            .take()
+            .unwrap_or_else(|| learning_symmetries(graph, use_symmetries));
+        let (key, automorphism) = symmetries.canonical_state(
+            graph,
+            self.number_of_cops,
+            Some(cop_positions),
+            robber_position,
+        );
+        let number_of_moves = robber_number_of_moves(graph, robber_position);
+
+        let choice = self.learning.choose(key, number_of_moves, rng);
+        let new_robber_position =
+            decode_robber_move(graph, &symmetries, automorphism, robber_position, choice);
+        self.learning.symmetries = Some(symmetries);
+        new_robber_position
+    }
+}
+
+impl RobberStrategy for QLearningRobber {
+    fn start(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.choose(graph, cop_positions, None, rng)
+    }
+
+    fn step(
+        &mut self,
+        graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+        rng: &mut GameRng,
+    ) -> RobberPosition {
+        self.choose(graph, cop_positions, Some(robber_position), rng)
+    }
+
+    fn end(
+        &mut self,
+        _graph: &Graph,
+        cop_positions: &CopPositions,
+        robber_position: RobberPosition,
+    ) {
+        self.learning.end(!cop_positions.contains(&robber_position));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{template_graphs, Game, RandomCop, RandomRobber};
+    use rand::SeedableRng;
+
+    #[test]
+    fn q_table_moves_values_towards_their_targets() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut table = QTable::new(5);
+        table.update(2, 1.0, 0.5);
+        assert_eq!(table.value(2), 0.5);
+        assert_eq!(table.max_value(), 0.5);
+        assert!((0..20).all(|_| table.best_move(&mut rng) == 2));
+
+        // A losing move is never the best, as the unchanged moves are worth 0.
+        table.update(2, -1.0, 1.0);
+        assert_eq!(table.value(2), -1.0);
+        assert_eq!(table.max_value(), 0.0);
+        assert!((0..20).all(|_| table.best_move(&mut rng) != 2));
+        assert_eq!(table.best_values(2), vec![(0, 0.0), (1, 0.0)]);
+        assert_eq!(table.best_values(5).last(), Some(&(2, -1.0)));
+    }
+
+    #[test]
+    fn last_move_is_rewarded_at_the_end() {
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut learning = QLearning::new(QLearningSettings {
+            epsilon: 0.0,
+            ..QLearningSettings::default()
+        });
+        let first = learning.choose(7, 3, &mut rng);
+        learning.end(true);
+        assert_eq!(learning.tables[&7].value(first), 0.1);
+        // The won move is now the best move.
+        assert_eq!(learning.choose(7, 3, &mut rng), first);
+        learning.end(false);
+        assert!((learning.tables[&7].value(first) - -0.01).abs() < 1e-9);
+    }
+
+    // The number of matches the cop wins out of the last 500 of 3000 on Path5.
+    fn late_cop_wins(cop: Box<dyn CopStrategy>) -> u32 {
+        let mut game = Game::new(
+            &template_graphs()[1],
+            3,
+            cop,
+            Box::new(RandomRobber::new()),
+            0,
+        );
+        while game.score[0] + game.score[1] < 2500 {
+            game.update();
+        }
+        let early_cop_wins = game.score[0];
+        while game.score[0] + game.score[1] < 3000 {
+            game.update();
+        }
+        game.score[0] - early_cop_wins
+    }
+
+    #[test]
+    fn cop_learns_to_catch_the_robber() {
+        let learned = late_cop_wins(Box::new(QLearningCop::new(1, QLearningSettings::default())));
+        let random = late_cop_wins(Box::new(RandomCop::new(1)));
+        assert!(learned > random, "{learned} <= {random}");
+    }
+}
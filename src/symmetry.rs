@@ -0,0 +1,234 @@
+use crate::game::{encode_state, CopPositions, Graph, RobberPosition, StateKey};
+
+// We stop searching for automorphisms after finding this many, e.g. a complete graph
+// has vertices! automorphisms. Any subset of the automorphisms is still correct to use,
+// some equivalent positions just don't share a bag.
+const MAX_AUTOMORPHISMS: usize = 1_000;
+// We also stop after trying this many partial assignments of vertices.
+const MAX_SEARCH_STEPS: usize = 1_000_000;
+
+/// The automorphisms of a graph: the permutations of its vertices which map edges to edges.
+/// Positions which an automorphism maps to each other are equivalent, so MENACE players
+/// can learn them with a single bag.
+#[derive(Clone)]
+pub struct Symmetries {
+    // automorphisms[a][v] is the image of v under automorphism a. The first one is the identity.
+    automorphisms: Vec<Vec<usize>>,
+    inverses: Vec<Vec<usize>>,
+}
+
+impl Symmetries {
+    /// Finds the automorphisms of the graph by backtracking.
+    pub fn new(graph: &Graph) -> Symmetries {
+        let number_of_vertices = graph.vertices.len();
+        let mut adjacent = vec![false; number_of_vertices * number_of_vertices];
+        for (v, neighbours) in graph.adjacency_list.iter().enumerate() {
+            for &u in neighbours {
+                adjacent[v * number_of_vertices + u] = true;
+                adjacent[u * number_of_vertices + v] = true;
+            }
+        }
+        let is_adjacent = |v: usize, u: usize| adjacent[v * number_of_vertices + u];
+        let degree = |v: usize| {
+            (0..number_of_vertices)
+                .filter(|&u| is_adjacent(v, u))
+                .count()
+        };
+        let degrees: Vec<_> = (0..number_of_vertices).map(degree).collect();
+
+        let mut symmetries = Self::trivial(graph);
+
+        // We assign images to the vertices in order. image[v] is the image of vertex v
+        // and candidates[v] is the next image to try for it.
+        let mut image = vec![usize::MAX; number_of_vertices];
+        let mut used = vec![false; number_of_vertices];
+        let mut candidates = vec![0; number_of_vertices];
+        let mut v = 0;
+        let mut steps = 0;
+        while v < number_of_vertices
+            && symmetries.automorphisms.len() < MAX_AUTOMORPHISMS
+            && steps < MAX_SEARCH_STEPS
+        {
+            steps += 1;
+            if image[v] != usize::MAX {
+                used[image[v]] = false;
+                image[v] = usize::MAX;
+            }
+
+            let next_image = (candidates[v]..number_of_vertices).find(|&u| {
+                !used[u]
+                    && degrees[u] == degrees[v]
+                    && (0..v).all(|w| is_adjacent(v, w) == is_adjacent(u, image[w]))
+            });
+            match next_image {
+                Some(u) => {
+                    image[v] = u;
+                    used[u] = true;
+                    candidates[v] = u + 1;
+                    if v + 1 == number_of_vertices {
+                        // The identity was added by trivial.
+                        if image.iter().enumerate().any(|(w, &u)| w != u) {
+                            symmetries.push(image.clone());
+                        }
+                    } else {
+                        v += 1;
+                        candidates[v] = 0;
+                    }
+                }
+                None => {
+                    // We've tried every image for v, so we backtrack.
+                    if v == 0 {
+                        break;
+                    }
+                    v -= 1;
+                }
+            }
+        }
+
+        symmetries
+    }
+
+    /// Only the identity, so no two positions are equivalent.
+    pub fn trivial(graph: &Graph) -> Symmetries {
+        let identity: Vec<_> = (0..graph.vertices.len()).collect();
+        Symmetries {
+            automorphisms: vec![identity.clone()],
+            inverses: vec![identity],
+        }
+    }
+
+    fn push(&mut self, automorphism: Vec<usize>) {
+        let mut inverse = vec![0; automorphism.len()];
+        for (v, &u) in automorphism.iter().enumerate() {
+            inverse[u] = v;
+        }
+        self.automorphisms.push(automorphism);
+        self.inverses.push(inverse);
+    }
+
+    pub fn number_of_automorphisms(&self) -> usize {
+        self.automorphisms.len()
+    }
+
+    /// The image of a vertex under an automorphism.
+    pub fn map(&self, automorphism: usize, vertex: usize) -> usize {
+        self.automorphisms[automorphism][vertex]
+    }
+
+    /// The vertex which an automorphism maps to `vertex`.
+    pub fn map_back(&self, automorphism: usize, vertex: usize) -> usize {
+        self.inverses[automorphism][vertex]
+    }
+
+    /// The key of the equivalent position with the smallest key, and the automorphism
+    /// which maps the position to it. The cop positions of the key are sorted.
+    pub fn canonical_state(
+        &self,
+        graph: &Graph,
+        number_of_cops: u8,
+        cop_positions: Option<&CopPositions>,
+        robber_position: Option<RobberPosition>,
+    ) -> (StateKey, usize) {
+        (0..self.automorphisms.len())
+            .map(|automorphism| {
+                let cop_positions = cop_positions.map(|cop_positions| {
+                    let mut cop_positions: CopPositions = cop_positions
+                        .iter()
+                        .map(|&cop_position| self.map(automorphism, cop_position))
+                        .collect();
+                    // The cops are interchangeable, so only the set of their positions matters.
+                    cop_positions.sort_unstable();
+                    cop_positions
+                });
+                let robber_position =
+                    robber_position.map(|robber_position| self.map(automorphism, robber_position));
+                let key = encode_state(
+                    graph,
+                    number_of_cops,
+                    cop_positions.as_ref(),
+                    robber_position,
+                );
+                (key, automorphism)
+            })
+            .min()
+            // There is always the identity.
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::template_graphs;
+
+    #[test]
+    fn finds_the_automorphisms_of_the_templates() {
+        let graphs = template_graphs();
+        let counts: Vec<_> = graphs
+            .iter()
+            .map(|graph| Symmetries::new(graph).number_of_automorphisms())
+            .collect();
+        // Path2 and Path5 can be reversed, and the Hexagon has 6 rotations and 6 reflections.
+        assert_eq!(counts, vec![2, 2, 12]);
+        assert_eq!(Symmetries::trivial(&graphs[2]).number_of_automorphisms(), 1);
+    }
+
+    #[test]
+    fn automorphisms_map_edges_to_edges() {
+        let graph = &template_graphs()[2];
+        let symmetries = Symmetries::new(graph);
+        for automorphism in 0..symmetries.number_of_automorphisms() {
+            for (v, neighbours) in graph.adjacency_list.iter().enumerate() {
+                assert_eq!(
+                    symmetries.map_back(automorphism, symmetries.map(automorphism, v)),
+                    v
+                );
+                for &u in neighbours {
+                    let image = symmetries.map(automorphism, u);
+                    assert!(graph.adjacency_list[symmetries.map(automorphism, v)].contains(&image));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn equivalent_positions_share_a_state() {
+        let graph = &template_graphs()[2];
+        let symmetries = Symmetries::new(graph);
+        let canonical_key = |cop_position: usize, robber_position: usize| {
+            symmetries
+                .canonical_state(graph, 1, Some(&vec![cop_position]), Some(robber_position))
+                .0
+        };
+        // Every position with the robber opposite the cop is a rotation of the others.
+        for cop_position in 0..6 {
+            assert_eq!(
+                canonical_key(cop_position, (cop_position + 3) % 6),
+                canonical_key(0, 3)
+            );
+        }
+        assert_ne!(canonical_key(0, 1), canonical_key(0, 2));
+
+        // The automorphism maps the position to its canonical state.
+        let (key, automorphism) = symmetries.canonical_state(graph, 1, Some(&vec![4]), Some(2));
+        let cop_positions = vec![symmetries.map(automorphism, 4)];
+        let robber_position = symmetries.map(automorphism, 2);
+        assert_eq!(
+            encode_state(graph, 1, Some(&cop_positions), Some(robber_position)),
+            key
+        );
+    }
+
+    #[test]
+    fn swapped_cops_share_a_state() {
+        let graph = &template_graphs()[1];
+        let symmetries = Symmetries::trivial(graph);
+        let canonical_key = |cop_positions: CopPositions| {
+            symmetries
+                .canonical_state(graph, 2, Some(&cop_positions), Some(2))
+                .0
+        };
+        assert_eq!(canonical_key(vec![0, 4]), canonical_key(vec![4, 0]));
+        assert_ne!(canonical_key(vec![0, 4]), canonical_key(vec![0, 3]));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::game::{Game, Graph, StrategyRegistry, StrategySettings, Turn};
+
+/// Every cop algorithm plays every robber algorithm on every graph.
+#[derive(Clone)]
+pub struct Tournament {
+    pub graphs: Vec<Graph>,
+    /// The registry names of the algorithms.
+    pub cops: Vec<String>,
+    pub robbers: Vec<String>,
+    pub number_of_cops: u8,
+    pub number_of_steps: u8,
+    /// The number of matches of every pairing.
+    pub number_of_matches: u32,
+    pub cop_settings: StrategySettings,
+    pub robber_settings: StrategySettings,
+    /// Whether every pairing starts with new players, so learning players start from scratch.
+    /// Otherwise each algorithm keeps what it learned against its previous opponents on the
+    /// same graph. Every graph starts with new players, as positions differ between graphs.
+    pub reset_learners: bool,
+    pub seed: u64,
+}
+
+/// The results of the pairings played so far.
+#[derive(Clone)]
+pub struct TournamentResults {
+    number_of_matches: u32,
+    // cop_wins[graph][cop][robber] is the number of matches the cops won in a pairing.
+    cop_wins: Vec<Vec<Vec<Option<u32>>>>,
+}
+
+impl TournamentResults {
+    pub fn new(tournament: &Tournament) -> Self {
+        Self {
+            number_of_matches: tournament.number_of_matches,
+            cop_wins: vec![
+                vec![vec![None; tournament.robbers.len()]; tournament.cops.len()];
+                tournament.graphs.len()
+            ],
+        }
+    }
+
+    /// The fraction of the matches of a pairing won by the cops, or None if it hasn't been played yet.
+    pub fn cop_win_rate(&self, graph: usize, cop: usize, robber: usize) -> Option<f64> {
+        self.cop_wins[graph][cop][robber]
+            .map(|cop_wins| cop_wins as f64 / self.number_of_matches.max(1) as f64)
+    }
+
+    /// The fraction of the matches of a cop and a robber won by the cops over all the graphs
+    /// they have played on so far.
+    pub fn total_cop_win_rate(&self, cop: usize, robber: usize) -> Option<f64> {
+        let cop_wins: Vec<_> = self
+            .cop_wins
+            .iter()
+            .filter_map(|cop_wins| cop_wins[cop][robber])
+            .collect();
+        let number_of_matches = cop_wins.len() as f64 * self.number_of_matches.max(1) as f64;
+        (!cop_wins.is_empty()).then(|| cop_wins.iter().sum::<u32>() as f64 / number_of_matches)
+    }
+
+    pub fn set_cop_wins(&mut self, graph: usize, cop: usize, robber: usize, cop_wins: u32) {
+        self.cop_wins[graph][cop][robber] = Some(cop_wins);
+    }
+
+    pub fn number_of_played_pairings(&self) -> usize {
+        self.cop_wins
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|cop_wins| cop_wins.is_some())
+            .count()
+    }
+
+    pub fn number_of_pairings(&self) -> usize {
+        self.cop_wins.iter().flatten().flatten().count()
+    }
+}
+
+impl Tournament {
+    // Plays number_of_matches matches of a new game and returns the number of matches
+    // the cops won. Stops early and returns None if stop returns true between matches.
+    fn play_pairing(&self, game: &mut Game, stop: &impl Fn() -> bool) -> Option<u32> {
+        let mut number_of_matches = 0;
+        while number_of_matches < self.number_of_matches {
+            if stop() {
+                return None;
+            }
+            game.update();
+            if game.turn == Turn::Over {
+                number_of_matches += 1;
+                // We start the next match, so its first update is a move.
+                game.update();
+            }
+        }
+        Some(game.score[0])
+    }
+
+    /// Plays every pairing, calling on_result with the graph, cop and robber indices and the
+    /// number of cop wins after each one. Stops early if stop returns true.
+    pub fn play(
+        &self,
+        registry: &StrategyRegistry,
+        mut on_result: impl FnMut(usize, usize, usize, u32),
+        stop: impl Fn() -> bool,
+    ) -> Result<(), String> {
+        let create_cop = |name: &str| {
+            registry
+                .create_cop(name, self.number_of_cops, &self.cop_settings)
+                .ok_or_else(|| format!("Unknown cop algorithm {name}"))
+        };
+        let create_robber = |name: &str| {
+            registry
+                .create_robber(name, self.number_of_cops, &self.robber_settings)
+                .ok_or_else(|| format!("Unknown robber algorithm {name}"))
+        };
+
+        // We check every algorithm can play on every graph before playing any pairing.
+        for graph in &self.graphs {
+            for name in &self.cops {
+                registry.check_cop(name, graph, self.number_of_cops)?;
+            }
+            for name in &self.robbers {
+                registry.check_robber(name, graph, self.number_of_cops)?;
+            }
+        }
+
+        let mut pairing_seed = self.seed;
+        for (graph_index, graph) in self.graphs.iter().enumerate() {
+            // The players of each algorithm, which play all their pairings on the graph
+            // unless we reset them.
+            let mut cops = self
+                .cops
+                .iter()
+                .map(|name| create_cop(name).map(Some))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut robbers = self
+                .robbers
+                .iter()
+                .map(|name| create_robber(name).map(Some))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for cop_index in 0..self.cops.len() {
+                for robber_index in 0..self.robbers.len() {
+                    let (cop, robber) = if self.reset_learners {
+                        (
+                            create_cop(&self.cops[cop_index])?,
+                            create_robber(&self.robbers[robber_index])?,
+                        )
+                    } else {
+                        // We put the players back after every pairing, so we can unwrap.
+                        (
+                            cops[cop_index].take().unwrap(),
+                            robbers[robber_index].take().unwrap(),
+                        )
+                    };
+
+                    let mut game =
+                        Game::new(graph, self.number_of_steps, cop, robber, pairing_seed);
+                    game.max_recorded_matches = 0;
+                    let Some(cop_wins) = self.play_pairing(&mut game, &stop) else {
+                        return Ok(());
+                    };
+                    pairing_seed = pairing_seed.wrapping_add(1);
+                    cops[cop_index] = Some(game.cop);
+                    robbers[robber_index] = Some(game.robber);
+                    on_result(graph_index, cop_index, robber_index, cop_wins);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::template_graphs;
+
+    fn tournament(cops: &[&str], robbers: &[&str]) -> Tournament {
+        let graphs = template_graphs();
+        Tournament {
+            graphs: vec![graphs[1].clone(), graphs[2].clone()],
+            cops: cops.iter().map(|name| name.to_string()).collect(),
+            robbers: robbers.iter().map(|name| name.to_string()).collect(),
+            number_of_cops: 1,
+            number_of_steps: 6,
+            number_of_matches: 10,
+            cop_settings: StrategySettings::default(),
+            robber_settings: StrategySettings::default(),
+            reset_learners: true,
+            seed: 3,
+        }
+    }
+
+    // Plays the tournament and returns the results in the order they were reported.
+    fn play(tournament: &Tournament) -> Vec<(usize, usize, usize, u32)> {
+        let mut results = vec![];
+        tournament
+            .play(
+                &StrategyRegistry::default(),
+                |graph, cop, robber, cop_wins| results.push((graph, cop, robber, cop_wins)),
+                || false,
+            )
+            .unwrap();
+        results
+    }
+
+    #[test]
+    fn plays_every_pairing() {
+        let tournament = tournament(&["Random", "Chaser"], &["Menace", "Evasive"]);
+        let results = play(&tournament);
+        let pairings: Vec<_> = results
+            .iter()
+            .map(|&(graph, cop, robber, _)| (graph, cop, robber))
+            .collect();
+        assert_eq!(
+            pairings,
+            vec![
+                (0, 0, 0),
+                (0, 0, 1),
+                (0, 1, 0),
+                (0, 1, 1),
+                (1, 0, 0),
+                (1, 0, 1),
+                (1, 1, 0),
+                (1, 1, 1),
+            ]
+        );
+        assert!(results.iter().all(|&(_, _, _, cop_wins)| cop_wins <= 10));
+        // The chaser always catches the robber on Path5, but the evasive robber
+        // always escapes a single cop on the Hexagon.
+        assert_eq!(results[2].3, 10);
+        assert_eq!(results[3].3, 10);
+        assert_eq!(results[7].3, 0);
+
+        // The tournament is reproducible from its seed.
+        assert_eq!(play(&tournament), results);
+    }
+
+    #[test]
+    fn stops_between_matches() {
+        let tournament = tournament(&["Random"], &["Random"]);
+        let mut number_of_results = 0;
+        let result = tournament.play(
+            &StrategyRegistry::default(),
+            |_, _, _, _| number_of_results += 1,
+            || true,
+        );
+        assert!(result.is_ok());
+        assert_eq!(number_of_results, 0);
+    }
+
+    #[test]
+    fn checks_the_algorithms_before_playing() {
+        let registry = StrategyRegistry::default();
+        let mut number_of_results = 0;
+        let result = tournament(&["Random", "Unknown"], &["Random"]).play(
+            &registry,
+            |_, _, _, _| number_of_results += 1,
+            || false,
+        );
+        assert!(result.is_err());
+
+        let mut tournament = tournament(&["Random"], &["Optimal"]);
+        tournament.number_of_cops = 8;
+        let result = tournament.play(&registry, |_, _, _, _| number_of_results += 1, || false);
+        assert!(result.is_err());
+        assert_eq!(number_of_results, 0);
+    }
+
+    #[test]
+    fn win_rates() {
+        let tournament = tournament(&["Random"], &["Random", "Evasive"]);
+        let mut results = TournamentResults::new(&tournament);
+        assert_eq!(results.number_of_pairings(), 4);
+        assert_eq!(results.total_cop_win_rate(0, 0), None);
+
+        results.set_cop_wins(0, 0, 0, 5);
+        results.set_cop_wins(1, 0, 0, 10);
+        results.set_cop_wins(1, 0, 1, 2);
+        assert_eq!(results.number_of_played_pairings(), 3);
+        assert_eq!(results.cop_win_rate(0, 0, 0), Some(0.5));
+        assert_eq!(results.cop_win_rate(0, 0, 1), None);
+        assert_eq!(results.total_cop_win_rate(0, 0), Some(0.75));
+        assert_eq!(results.total_cop_win_rate(0, 1), Some(0.2));
+    }
+}